@@ -0,0 +1,162 @@
+//! Append-only audit log of hook decisions, with age-based pruning.
+//!
+//! When `--audit-log <path>` is passed to `permission-request` or
+//! `pre-tool-use`, every emitted decision is appended as a JSON line,
+//! giving users a reviewable trail of what the agent was blocked (or asked
+//! to confirm) from doing. Borrowing zoxide's retention approach, each
+//! write also drops entries older than `--audit-retention-days` (default
+//! [`DEFAULT_RETENTION_DAYS`]) so the file self-prunes instead of growing
+//! unbounded.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Default retention window, in days, for `--audit-retention-days`.
+pub const DEFAULT_RETENTION_DAYS: u64 = 90;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// One recorded hook decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Unix timestamp (seconds) the decision was made.
+    pub timestamp: u64,
+    /// The Claude Code tool this decision was about (`Bash`, `Edit`, ...).
+    pub tool_name: String,
+    /// The command or file path the decision concerned.
+    pub subject: String,
+    /// `"deny"`, `"ask"`, or `"allow"`.
+    pub behavior: String,
+    pub reason: String,
+}
+
+impl AuditRecord {
+    /// Build a record timestamped with the current time.
+    #[must_use]
+    pub fn now(tool_name: &str, subject: &str, behavior: &str, reason: &str) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            timestamp,
+            tool_name: tool_name.to_string(),
+            subject: subject.to_string(),
+            behavior: behavior.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Append `record` to the audit log at `path`: read the existing lines,
+/// drop any entry older than `retention_days` relative to `record`'s
+/// timestamp, append the new record, and rewrite the file atomically
+/// (write to a sibling temp file, then rename over the target).
+pub fn append(path: &Path, record: &AuditRecord, retention_days: u64) -> io::Result<()> {
+    let cutoff = record
+        .timestamp
+        .saturating_sub(retention_days.saturating_mul(SECONDS_PER_DAY));
+
+    let mut lines: Vec<String> = fs::read_to_string(path)
+        .map(|contents| {
+            contents
+                .lines()
+                .filter(|line| {
+                    serde_json::from_str::<AuditRecord>(line)
+                        .is_ok_and(|r| r.timestamp >= cutoff)
+                })
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    lines.push(serde_json::to_string(record)?);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, lines.join("\n") + "\n")?;
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log_path(label: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "agent-hooks-audit-test-{label}-{}.jsonl",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_append_writes_a_readable_json_line() {
+        let path = temp_log_path("append");
+        let record = AuditRecord::now("Bash", "rm -rf /", "deny", "rm is forbidden");
+        append(&path, &record, DEFAULT_RETENTION_DAYS).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let parsed: AuditRecord = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(parsed.subject, "rm -rf /");
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_append_prunes_entries_older_than_retention() {
+        let path = temp_log_path("prune");
+        let old = AuditRecord {
+            timestamp: 1_000,
+            tool_name: "Bash".to_string(),
+            subject: "old command".to_string(),
+            behavior: "deny".to_string(),
+            reason: String::new(),
+        };
+        let new = AuditRecord {
+            timestamp: 1_000 + 2 * SECONDS_PER_DAY,
+            tool_name: "Bash".to_string(),
+            subject: "new command".to_string(),
+            behavior: "deny".to_string(),
+            reason: String::new(),
+        };
+        append(&path, &old, 90).unwrap();
+        append(&path, &new, 1).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("new command"));
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_append_keeps_entries_within_retention() {
+        let path = temp_log_path("keep");
+        let first = AuditRecord {
+            timestamp: 10_000,
+            tool_name: "Bash".to_string(),
+            subject: "first".to_string(),
+            behavior: "ask".to_string(),
+            reason: String::new(),
+        };
+        let second = AuditRecord {
+            timestamp: 10_000 + SECONDS_PER_DAY,
+            tool_name: "Bash".to_string(),
+            subject: "second".to_string(),
+            behavior: "ask".to_string(),
+            reason: String::new(),
+        };
+        append(&path, &first, 90).unwrap();
+        append(&path, &second, 90).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        fs::remove_file(&path).ok();
+    }
+}
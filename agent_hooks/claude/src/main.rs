@@ -1,7 +1,16 @@
-use agent_hooks::{check_destructive_find, check_rust_allow_attributes, is_rm_command, is_rust_file, RustAllowCheckResult};
+use agent_hooks::{
+    disallowed_lints, is_rust_file, newly_disallowed_lints, scan_command, suggest_edit_for,
+    AllowKind, DangerDecision, DenyRustAllowOptions,
+};
 use seahorse::{App, Command, Context, Flag, FlagType};
 use serde::{Deserialize, Serialize};
 use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+mod audit;
+mod policy;
+
+use policy::{is_valid_path_glob, Policy, PolicyDecision};
 
 // ============================================================================
 // Claude Code specific types
@@ -36,6 +45,9 @@ pub struct HookInput {
 #[non_exhaustive]
 pub struct ToolInput {
     pub command: Option<String>,
+    /// For Edit tool: the prior content being replaced, used to diff out
+    /// the lines this edit actually introduces
+    pub old_string: Option<String>,
     pub new_string: Option<String>,
     pub content: Option<String>,
     pub file_path: Option<String>,
@@ -90,6 +102,20 @@ pub struct HookSpecificOutput {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub permission_decision_reason: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_edit: Option<SuggestedEdit>,
+}
+
+/// A rustfix-style suggested fix for a denied attribute: the byte span to
+/// replace and the text to replace it with, so a cooperating agent can
+/// auto-apply the correction instead of re-parsing the file to find it.
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+pub struct SuggestedEdit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -117,17 +143,192 @@ fn output_hook_result(output: &HookOutput) {
     }
 }
 
+/// Reduce a `HookOutput` to the `(behavior, reason)` pair an audit log entry
+/// records, regardless of whether it came from the `decision` or
+/// `permission_decision` field.
+fn decision_summary(output: &HookOutput) -> (String, String) {
+    let out = &output.hook_specific_output;
+
+    if let Some(decision) = &out.decision {
+        let behavior = match decision.behavior {
+            DecisionBehavior::Deny => "deny",
+            DecisionBehavior::Allow => "allow",
+        };
+        return (behavior.to_string(), decision.message.clone());
+    }
+
+    let behavior = match out.permission_decision {
+        Some(PermissionDecision::Deny) => "deny",
+        Some(PermissionDecision::Ask) => "ask",
+        Some(PermissionDecision::Allow) | None => "allow",
+    };
+    (
+        behavior.to_string(),
+        out.permission_decision_reason.clone().unwrap_or_default(),
+    )
+}
+
+/// Print `output` as the hook's JSON response, then, if `--audit-log` was
+/// passed, append a record of the decision for `tool_name`/`subject` to it.
+fn emit(c: &Context, output: &HookOutput, tool_name: &str, subject: &str) {
+    output_hook_result(output);
+
+    let Ok(log_path) = c.string_flag("audit-log") else {
+        return;
+    };
+    let retention_days = c
+        .string_flag("audit-retention-days")
+        .ok()
+        .and_then(|raw| raw.parse().ok())
+        .unwrap_or(audit::DEFAULT_RETENTION_DAYS);
+
+    let (behavior, reason) = decision_summary(output);
+    let record = audit::AuditRecord::now(tool_name, subject, &behavior, &reason);
+    let _ = audit::append(Path::new(&log_path), &record, retention_days);
+}
+
 // ============================================================================
 // Command handlers
 // ============================================================================
 
-fn permission_request_action(c: &Context) {
-    let block_rm = c.bool_flag("block-rm");
-    let confirm_destructive_find = c.bool_flag("confirm-destructive-find");
+/// Load the effective policy for `permission-request`: the `--policy` flag
+/// if given, else `~/.config/agent_hooks/policy.toml` if it exists, merged
+/// with the built-in defaults; falls back to the defaults outright if no
+/// policy file is found or it fails to parse.
+fn load_policy(c: &Context) -> Policy {
+    let path = c
+        .string_flag("policy")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(Policy::default_path);
+
+    let Some(path) = path.filter(|path| path.exists()) else {
+        return Policy::defaults();
+    };
+
+    match Policy::load(&path) {
+        Ok(policy) => policy.merged_with_defaults(),
+        Err(_) => Policy::defaults(),
+    }
+}
 
-    if !block_rm && !confirm_destructive_find {
+// ============================================================================
+// Policy management (`policy new/add/rm/ls`)
+// ============================================================================
+
+/// The policy file a `policy` subcommand should act on: the `--policy` flag
+/// if given, else the default path. Unlike `load_policy`, this never falls
+/// back to in-memory defaults - these subcommands need a concrete file to
+/// read and write.
+fn policy_target_path(c: &Context) -> Option<PathBuf> {
+    c.string_flag("policy")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(Policy::default_path)
+}
+
+fn policy_new_action(c: &Context) {
+    let Some(path) = policy_target_path(c) else {
+        eprintln!("policy new: could not determine a policy path (pass --policy)");
+        return;
+    };
+    if path.exists() {
+        eprintln!(
+            "policy new: {} already exists, not overwriting",
+            path.display()
+        );
         return;
     }
+    match Policy::defaults().save(&path) {
+        Ok(()) => println!("Wrote default policy to {}", path.display()),
+        Err(e) => eprintln!("policy new: failed to write {}: {e}", path.display()),
+    }
+}
+
+fn policy_add_action(c: &Context) {
+    let Some(path) = policy_target_path(c) else {
+        eprintln!("policy add: could not determine a policy path (pass --policy)");
+        return;
+    };
+    let mut policy = Policy::load(&path).unwrap_or_default();
+
+    if let Ok(name) = c.string_flag("deny-run") {
+        policy.deny_run.push(name);
+    }
+    if let Ok(name) = c.string_flag("allow-run") {
+        policy.allow_run.push(name);
+    }
+    if let Ok(name) = c.string_flag("confirm-run") {
+        policy.confirm_run.push(name);
+    }
+    if let Ok(pattern) = c.string_flag("deny-path") {
+        if !is_valid_path_glob(&pattern) {
+            eprintln!("policy add: '{pattern}' is not a valid path glob");
+            return;
+        }
+        policy.deny_write.push(pattern.clone());
+        policy.deny_read.push(pattern);
+    }
+    if let Ok(pattern) = c.string_flag("allow-path") {
+        if !is_valid_path_glob(&pattern) {
+            eprintln!("policy add: '{pattern}' is not a valid path glob");
+            return;
+        }
+        policy.allow_write.push(pattern.clone());
+        policy.allow_read.push(pattern);
+    }
+
+    match policy.save(&path) {
+        Ok(()) => println!("Updated {}", path.display()),
+        Err(e) => eprintln!("policy add: failed to write {}: {e}", path.display()),
+    }
+}
+
+fn policy_rm_action(c: &Context) {
+    let Some(path) = policy_target_path(c) else {
+        eprintln!("policy rm: could not determine a policy path (pass --policy)");
+        return;
+    };
+    let Ok(mut policy) = Policy::load(&path) else {
+        eprintln!("policy rm: failed to read {}", path.display());
+        return;
+    };
+
+    if let Ok(name) = c.string_flag("deny-run") {
+        policy.deny_run.retain(|n| n != &name);
+    }
+    if let Ok(name) = c.string_flag("allow-run") {
+        policy.allow_run.retain(|n| n != &name);
+    }
+    if let Ok(name) = c.string_flag("confirm-run") {
+        policy.confirm_run.retain(|n| n != &name);
+    }
+    if let Ok(pattern) = c.string_flag("deny-path") {
+        policy.deny_write.retain(|p| p != &pattern);
+        policy.deny_read.retain(|p| p != &pattern);
+    }
+    if let Ok(pattern) = c.string_flag("allow-path") {
+        policy.allow_write.retain(|p| p != &pattern);
+        policy.allow_read.retain(|p| p != &pattern);
+    }
+
+    match policy.save(&path) {
+        Ok(()) => println!("Updated {}", path.display()),
+        Err(e) => eprintln!("policy rm: failed to write {}: {e}", path.display()),
+    }
+}
+
+/// Print the effective policy (the on-disk file merged with the built-in
+/// defaults, or just the defaults if no file exists) as JSON.
+fn policy_ls_action(c: &Context) {
+    let policy = load_policy(c);
+    if let Ok(json) = serde_json::to_string_pretty(&policy) {
+        println!("{json}");
+    }
+}
+
+fn permission_request_action(c: &Context) {
+    let scan_commands = c.bool_flag("scan-commands");
 
     let Ok(data) = read_hook_input() else {
         return;
@@ -148,36 +349,80 @@ fn permission_request_action(c: &Context) {
         return;
     }
 
-    // Check for rm command
-    if block_rm && is_rm_command(cmd) {
-        output_hook_result(&HookOutput {
-            hook_specific_output: HookSpecificOutput {
-                hook_event_name: HookEventName::PermissionRequest,
-                decision: Some(Decision {
-                    behavior: DecisionBehavior::Deny,
-                    message: "rm is forbidden. Use trash command to delete files. Example: trash <path...>".to_string(),
-                }),
-                permission_decision: None,
-                permission_decision_reason: None,
-            },
-        });
-        return;
+    // Check the command/path policy (covers block-rm as a built-in rule)
+    let policy = load_policy(c);
+    let cwd = std::env::current_dir().unwrap_or_default();
+    match policy.evaluate(cmd, &cwd) {
+        PolicyDecision::Deny(message) => {
+            emit(
+                c,
+                &HookOutput {
+                    hook_specific_output: HookSpecificOutput {
+                        hook_event_name: HookEventName::PermissionRequest,
+                        decision: Some(Decision {
+                            behavior: DecisionBehavior::Deny,
+                            message,
+                        }),
+                        permission_decision: None,
+                        permission_decision_reason: None,
+                        suggested_edit: None,
+                    },
+                },
+                "Bash",
+                cmd,
+            );
+            return;
+        }
+        PolicyDecision::Ask(reason) => {
+            emit(
+                c,
+                &HookOutput {
+                    hook_specific_output: HookSpecificOutput {
+                        hook_event_name: HookEventName::PermissionRequest,
+                        decision: None,
+                        permission_decision: Some(PermissionDecision::Ask),
+                        permission_decision_reason: Some(reason),
+                        suggested_edit: None,
+                    },
+                },
+                "Bash",
+                cmd,
+            );
+            return;
+        }
+        PolicyDecision::Allow => {}
     }
 
-    // Check for destructive find command
-    if confirm_destructive_find {
-        if let Some(description) = check_destructive_find(cmd) {
-            output_hook_result(&HookOutput {
-                hook_specific_output: HookSpecificOutput {
-                    hook_event_name: HookEventName::PermissionRequest,
-                    decision: None,
-                    permission_decision: Some(PermissionDecision::Ask),
-                    permission_decision_reason: Some(format!(
-                        "Destructive find command detected: {description}. \
-                         This operation may delete or modify files. Please confirm."
-                    )),
+    // Run the pluggable danger scanner: the built-in rm/find detectors plus
+    // dd/mkfs/git-clean/truncate/chmod-recursive, and whatever the policy
+    // file enables, disables, or adds on top.
+    if scan_commands {
+        if let Some(finding) = scan_command(cmd, &policy.detectors()) {
+            let permission_decision = match finding.decision {
+                DangerDecision::Deny => PermissionDecision::Deny,
+                DangerDecision::Ask => PermissionDecision::Ask,
+            };
+            let confirm_hint = match finding.decision {
+                DangerDecision::Deny => "This command is blocked.",
+                DangerDecision::Ask => "Please confirm.",
+            };
+            emit(
+                c,
+                &HookOutput {
+                    hook_specific_output: HookSpecificOutput {
+                        hook_event_name: HookEventName::PermissionRequest,
+                        decision: None,
+                        permission_decision: Some(permission_decision),
+                        permission_decision_reason: Some(format!(
+                            "{} detected: {}. {confirm_hint}",
+                            finding.detector, finding.description
+                        )),
+                        suggested_edit: None,
+                    },
                 },
-            });
+                "Bash",
+                cmd,
+            );
         }
     }
 }
@@ -224,70 +469,191 @@ fn pre_tool_use_action(c: &Context) {
 
     let expect_flag = c.bool_flag("expect");
     let additional_context = c.string_flag("additional-context").ok();
+    let parse_lint_csv = |flag: &str| {
+        c.string_flag(flag)
+            .ok()
+            .map(|raw| {
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|lint| !lint.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let allow_lints = parse_lint_csv("allow-lints");
+    let deny_lints = parse_lint_csv("deny-lints");
 
-    let check_result = check_rust_allow_attributes(content);
+    let options = DenyRustAllowOptions {
+        allow_lints,
+        deny_lints,
+    };
+    let disallowed = match tool_name {
+        // Only flag attributes this edit actually introduces (added or
+        // newly uncommented); one merely moved or left untouched is fine.
+        ToolName::Edit => {
+            let old_content = tool_input.old_string.as_deref().unwrap_or_default();
+            newly_disallowed_lints(old_content, content, &options)
+        }
+        // No prior content for Write, so there's nothing to diff against.
+        _ => disallowed_lints(content, &options),
+    };
+    let allow_names: Vec<&str> = disallowed
+        .iter()
+        .filter(|lint| lint.kind == AllowKind::Allow)
+        .map(|lint| lint.lint.as_str())
+        .collect();
+    let expect_names: Vec<&str> = disallowed
+        .iter()
+        .filter(|lint| lint.kind == AllowKind::Expect)
+        .map(|lint| lint.lint.as_str())
+        .collect();
 
     let denial_reason = if expect_flag {
         // --expect: only deny #[allow], allow #[expect]
-        match check_result {
-            RustAllowCheckResult::HasAllow | RustAllowCheckResult::HasBoth => {
-                let mut msg = "Adding #[allow(...)] or #![allow(...)] attributes is not permitted. \
-                               Use #[expect(...)] instead, which will warn when the lint is no longer triggered."
-                    .to_string();
-                if let Some(ref ctx) = additional_context {
-                    msg.push(' ');
-                    msg.push_str(ctx);
-                }
-                Some(msg)
+        if allow_names.is_empty() {
+            None
+        } else {
+            let mut msg = format!(
+                "Adding #[allow({})] or #![allow(...)] attributes is not permitted. \
+                 Use #[expect(...)] instead, which will warn when the lint is no longer triggered.",
+                allow_names.join(", ")
+            );
+            if let Some(ref ctx) = additional_context {
+                msg.push(' ');
+                msg.push_str(ctx);
             }
-            _ => None,
+            Some(msg)
         }
     } else {
         // no --expect: deny both #[allow] and #[expect]
-        match check_result {
-            RustAllowCheckResult::Ok => None,
-            RustAllowCheckResult::HasBoth => {
-                let mut msg = "Adding #[allow(...)] or #[expect(...)] attributes is not permitted. \
-                               Fix the underlying issue instead of suppressing the warning."
-                    .to_string();
-                if let Some(ref ctx) = additional_context {
-                    msg.push(' ');
-                    msg.push_str(ctx);
-                }
-                Some(msg)
-            }
-            RustAllowCheckResult::HasAllow => {
-                let mut msg = "Adding #[allow(...)] or #![allow(...)] attributes is not permitted. \
-                               Fix the underlying issue instead of suppressing the warning."
-                    .to_string();
-                if let Some(ref ctx) = additional_context {
-                    msg.push(' ');
-                    msg.push_str(ctx);
-                }
-                Some(msg)
-            }
-            RustAllowCheckResult::HasExpect => {
-                let mut msg = "Adding #[expect(...)] or #![expect(...)] attributes is not permitted. \
-                               Fix the underlying issue instead of suppressing the warning."
-                    .to_string();
-                if let Some(ref ctx) = additional_context {
-                    msg.push(' ');
-                    msg.push_str(ctx);
-                }
-                Some(msg)
+        let mut msg = if !allow_names.is_empty() && !expect_names.is_empty() {
+            format!(
+                "Adding #[allow({})] or #[expect({})] attributes is not permitted. \
+                 Fix the underlying issue instead of suppressing the warning.",
+                allow_names.join(", "),
+                expect_names.join(", ")
+            )
+        } else if !allow_names.is_empty() {
+            format!(
+                "Adding #[allow({})] or #![allow(...)] attributes is not permitted. \
+                 Fix the underlying issue instead of suppressing the warning.",
+                allow_names.join(", ")
+            )
+        } else if !expect_names.is_empty() {
+            format!(
+                "Adding #[expect({})] or #![expect(...)] attributes is not permitted. \
+                 Fix the underlying issue instead of suppressing the warning.",
+                expect_names.join(", ")
+            )
+        } else {
+            String::new()
+        };
+
+        if msg.is_empty() {
+            None
+        } else {
+            if let Some(ref ctx) = additional_context {
+                msg.push(' ');
+                msg.push_str(ctx);
             }
+            Some(msg)
         }
     };
 
     if let Some(reason) = denial_reason {
-        output_hook_result(&HookOutput {
-            hook_specific_output: HookSpecificOutput {
-                hook_event_name: HookEventName::PreToolUse,
-                decision: None,
-                permission_decision: Some(PermissionDecision::Deny),
-                permission_decision_reason: Some(reason),
-            },
+        let suggested_edit = if expect_flag {
+            disallowed.iter().find(|lint| lint.kind == AllowKind::Allow)
+        } else {
+            disallowed.first()
+        }
+        .map(|lint| {
+            let edit = suggest_edit_for(content, lint, expect_flag);
+            SuggestedEdit {
+                start: edit.span.0,
+                end: edit.span.1,
+                replacement: edit.replacement,
+            }
         });
+
+        emit(
+            c,
+            &HookOutput {
+                hook_specific_output: HookSpecificOutput {
+                    hook_event_name: HookEventName::PreToolUse,
+                    decision: None,
+                    permission_decision: Some(PermissionDecision::Deny),
+                    permission_decision_reason: Some(reason),
+                    suggested_edit,
+                },
+            },
+            tool_name_label(tool_name),
+            file_path,
+        );
+    }
+}
+
+/// The `tool_name` string recorded in an audit log entry for `tool_name`.
+fn tool_name_label(tool_name: &ToolName) -> &'static str {
+    match tool_name {
+        ToolName::Edit => "Edit",
+        ToolName::Write => "Write",
+        _ => "Unknown",
+    }
+}
+
+// ============================================================================
+// Capability discovery
+// ============================================================================
+
+/// Hook protocol version this binary implements, independent of the crate's
+/// own semantic version - bumped only when the JSON shape of hook input or
+/// output changes.
+const HOOK_PROTOCOL_VERSION: (u32, u32) = (1, 0);
+
+/// A check this binary understands, named after the flag or policy feature
+/// that enables it, so a wrapper can introspect supported behavior before
+/// wiring this binary into `settings.json` instead of guessing from flags.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[non_exhaustive]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    BlockRm,
+    DangerScanner,
+    Policy,
+    DenyRustAllow,
+    Expect,
+    AllowDenyLints,
+    AuditLog,
+}
+
+/// JSON document printed by the `version` command so a wrapper can
+/// introspect this binary's capabilities before relying on them.
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub crate_version: String,
+    pub hook_protocol_version: (u32, u32),
+    pub capabilities: Vec<Capability>,
+}
+
+fn version_action(_c: &Context) {
+    let info = VersionInfo {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        hook_protocol_version: HOOK_PROTOCOL_VERSION,
+        capabilities: vec![
+            Capability::BlockRm,
+            Capability::DangerScanner,
+            Capability::Policy,
+            Capability::DenyRustAllow,
+            Capability::Expect,
+            Capability::AllowDenyLints,
+            Capability::AuditLog,
+        ],
+    };
+    if let Ok(json) = serde_json::to_string(&info) {
+        println!("{json}");
     }
 }
 
@@ -305,12 +671,28 @@ fn main() {
             Command::new("permission-request")
                 .description("Handle permission requests for Bash commands")
                 .flag(
-                    Flag::new("block-rm", FlagType::Bool)
-                        .description("Block rm command and suggest using trash instead"),
+                    Flag::new("scan-commands", FlagType::Bool).description(
+                        "Run the danger scanner (rm, destructive find, dd, mkfs, git clean \
+                         -fdx, truncate, recursive chmod, plus any detectors added or \
+                         disabled by the policy file)",
+                    ),
                 )
                 .flag(
-                    Flag::new("confirm-destructive-find", FlagType::Bool)
-                        .description("Ask for confirmation on destructive find commands"),
+                    Flag::new("policy", FlagType::String)
+                        .description(
+                            "Path to a policy.toml/.json file of command/path allow/deny rules \
+                             (default: ~/.config/agent_hooks/policy.toml). Always includes the \
+                             built-in block-rm rule.",
+                        ),
+                )
+                .flag(
+                    Flag::new("audit-log", FlagType::String)
+                        .description("Append each decision as a JSON line to this file"),
+                )
+                .flag(
+                    Flag::new("audit-retention-days", FlagType::String).description(
+                        "With --audit-log: drop entries older than this many days (default: 90)",
+                    ),
                 )
                 .action(permission_request_action),
         )
@@ -331,7 +713,107 @@ fn main() {
                             "With --deny-rust-allow: additional context message to append to the denial reason",
                         ),
                 )
+                .flag(
+                    Flag::new("allow-lints", FlagType::String)
+                        .description(
+                            "With --deny-rust-allow: comma-separated lint names (or glob-ish \
+                             prefixes like clippy::*) that may be allowed/expected; any lint not \
+                             listed here is denied",
+                        ),
+                )
+                .flag(
+                    Flag::new("deny-lints", FlagType::String)
+                        .description(
+                            "With --deny-rust-allow: comma-separated lint names (or glob-ish \
+                             prefixes) that are always denied, even if covered by --allow-lints",
+                        ),
+                )
+                .flag(
+                    Flag::new("audit-log", FlagType::String)
+                        .description("Append each decision as a JSON line to this file"),
+                )
+                .flag(
+                    Flag::new("audit-retention-days", FlagType::String).description(
+                        "With --audit-log: drop entries older than this many days (default: 90)",
+                    ),
+                )
                 .action(pre_tool_use_action),
+        )
+        .command(
+            Command::new("version")
+                .description(
+                    "Print a JSON document with the crate version, hook protocol version, \
+                     and supported capabilities",
+                )
+                .action(version_action),
+        )
+        .command(
+            Command::new("policy")
+                .description("Scaffold and edit the policy file without hand-editing TOML/JSON")
+                .command(
+                    Command::new("new")
+                        .description("Write a default policy file (default: ~/.config/agent_hooks/policy.toml)")
+                        .flag(
+                            Flag::new("policy", FlagType::String)
+                                .description("Path to write the policy file to"),
+                        )
+                        .action(policy_new_action),
+                )
+                .command(
+                    Command::new("add")
+                        .description("Append a rule to the policy file")
+                        .flag(
+                            Flag::new("policy", FlagType::String)
+                                .description("Path to the policy file to edit"),
+                        )
+                        .flag(Flag::new("deny-run", FlagType::String).description("Executable name to deny"))
+                        .flag(Flag::new("allow-run", FlagType::String).description("Executable name to allow"))
+                        .flag(
+                            Flag::new("confirm-run", FlagType::String)
+                                .description("Executable name to require confirmation for"),
+                        )
+                        .flag(
+                            Flag::new("deny-path", FlagType::String)
+                                .description("Path glob to deny reading and writing"),
+                        )
+                        .flag(
+                            Flag::new("allow-path", FlagType::String)
+                                .description("Path glob to allow reading and writing"),
+                        )
+                        .action(policy_add_action),
+                )
+                .command(
+                    Command::new("rm")
+                        .description("Remove a rule from the policy file")
+                        .flag(
+                            Flag::new("policy", FlagType::String)
+                                .description("Path to the policy file to edit"),
+                        )
+                        .flag(Flag::new("deny-run", FlagType::String).description("Executable name to stop denying"))
+                        .flag(Flag::new("allow-run", FlagType::String).description("Executable name to stop allowing"))
+                        .flag(
+                            Flag::new("confirm-run", FlagType::String)
+                                .description("Executable name to stop requiring confirmation for"),
+                        )
+                        .flag(
+                            Flag::new("deny-path", FlagType::String)
+                                .description("Path glob to stop denying"),
+                        )
+                        .flag(
+                            Flag::new("allow-path", FlagType::String)
+                                .description("Path glob to stop allowing"),
+                        )
+                        .action(policy_rm_action),
+                )
+                .command(
+                    Command::new("ls")
+                        .description("Print the effective policy (file merged with defaults) as JSON")
+                        .flag(
+                            Flag::new("policy", FlagType::String)
+                                .description("Path to the policy file to read"),
+                        )
+                        .action(policy_ls_action),
+                ),
         );
 
     app.run(args);
@@ -0,0 +1,521 @@
+//! Config-driven command/path policy for `permission-request`.
+//!
+//! Where `--block-rm` used to be the only rule, baked into the binary as a
+//! static regex, this loads a TOML or JSON policy file - `--policy <path>`,
+//! falling back to `~/.config/agent_hooks/policy.toml` - describing
+//! allow/deny rules for Bash commands, borrowing Deno's permission model:
+//! explicit allow/deny lists per resource kind, with deny always winning.
+
+use agent_hooks::{default_detectors, program_name, tokenize_shell, DangerDecision, Detector};
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+/// A loaded (or default) policy for `permission-request`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Policy {
+    /// Executable names that are always denied outright.
+    pub deny_run: Vec<String>,
+    /// Executable names allowed to run. When non-empty, any executable not
+    /// listed here is denied.
+    pub allow_run: Vec<String>,
+    /// Executable names permitted to run but that require confirmation
+    /// (`PermissionDecision::Ask`) rather than outright denial.
+    pub confirm_run: Vec<String>,
+    /// Path prefixes that may not be written to.
+    pub deny_write: Vec<String>,
+    /// Path prefixes that may be written to. When non-empty, any argument
+    /// path not covered by this list is denied.
+    pub allow_write: Vec<String>,
+    /// Path prefixes that may not be read from.
+    pub deny_read: Vec<String>,
+    /// Path prefixes that may be read from. When non-empty, any argument
+    /// path not covered by this list is denied.
+    pub allow_read: Vec<String>,
+    /// Names of built-in danger detectors (see `agent_hooks::default_detectors`)
+    /// to turn off, e.g. `["mkfs"]` to stop flagging `mkfs` invocations.
+    pub disabled_detectors: Vec<String>,
+    /// Extra danger detectors, checked after the (non-disabled) built-ins.
+    pub custom_detectors: Vec<CustomDetector>,
+}
+
+/// A user-defined entry in `custom_detectors`: a regex `pattern` matched
+/// case-insensitively against the whole command line.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CustomDetector {
+    pub name: String,
+    pub pattern: String,
+    pub decision: CustomDetectorDecision,
+    pub description: String,
+}
+
+/// Mirrors `agent_hooks::DangerDecision`, kept separate since the core crate
+/// doesn't depend on serde.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomDetectorDecision {
+    Deny,
+    Ask,
+}
+
+impl From<CustomDetectorDecision> for DangerDecision {
+    fn from(decision: CustomDetectorDecision) -> Self {
+        match decision {
+            CustomDetectorDecision::Deny => Self::Deny,
+            CustomDetectorDecision::Ask => Self::Ask,
+        }
+    }
+}
+
+/// Outcome of evaluating a command against a [`Policy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    Allow,
+    Deny(String),
+    Ask(String),
+}
+
+impl Policy {
+    /// The built-in ruleset: today's `block-rm`, expressed as a policy rule.
+    #[must_use]
+    pub fn defaults() -> Self {
+        Self {
+            deny_run: vec!["rm".to_string()],
+            ..Self::default()
+        }
+    }
+
+    /// Default config path: `~/.config/agent_hooks/policy.toml`.
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs_config_home().map(|home| home.join("agent_hooks").join("policy.toml"))
+    }
+
+    /// Load a policy file from disk, parsing as JSON if `path` ends in
+    /// `.json` and as TOML otherwise.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&contents)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        } else {
+            toml::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+    }
+
+    /// Serialize and write this policy to `path` atomically (write to a
+    /// sibling temp file, then rename over the target) so a reader never
+    /// observes a half-written file. Serializes as JSON if `path` ends in
+    /// `.json` and as TOML otherwise, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let contents = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_string_pretty(self)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        } else {
+            toml::to_string_pretty(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(&tmp_path, path)
+    }
+
+    /// Merge this (user-supplied) policy on top of the built-in defaults:
+    /// every list is the union of both, so a loaded file only ever adds
+    /// rules on top of `block-rm` rather than silently dropping it.
+    #[must_use]
+    pub fn merged_with_defaults(mut self) -> Self {
+        let defaults = Self::defaults();
+        self.deny_run.extend(defaults.deny_run);
+        self.allow_run.extend(defaults.allow_run);
+        self.confirm_run.extend(defaults.confirm_run);
+        self.deny_write.extend(defaults.deny_write);
+        self.allow_write.extend(defaults.allow_write);
+        self.deny_read.extend(defaults.deny_read);
+        self.allow_read.extend(defaults.allow_read);
+        self.disabled_detectors.extend(defaults.disabled_detectors);
+        self.custom_detectors.extend(defaults.custom_detectors);
+        self
+    }
+
+    /// The effective danger detectors: the built-ins minus any name listed
+    /// in `disabled_detectors`, followed by `custom_detectors` compiled to
+    /// `Detector`s. A custom detector whose pattern fails to compile is
+    /// skipped rather than rejecting the whole policy.
+    #[must_use]
+    pub fn detectors(&self) -> Vec<Detector> {
+        let mut detectors: Vec<Detector> = default_detectors()
+            .into_iter()
+            .filter(|detector| !self.disabled_detectors.iter().any(|name| name == &detector.name))
+            .collect();
+
+        detectors.extend(self.custom_detectors.iter().filter_map(|custom| {
+            Detector::from_pattern(
+                &custom.name,
+                &custom.pattern,
+                custom.decision.into(),
+                &custom.description,
+            )
+            .ok()
+        }));
+
+        detectors
+    }
+
+    /// Evaluate a Bash `command` string against this policy: extract the
+    /// leading executable name and any non-flag arguments, resolve each
+    /// argument as a path against `cwd` (mirroring Deno's
+    /// `resolve_from_cwd`), then check the executable against the run
+    /// lists and each resolved path against the write/read lists. Deny
+    /// always wins over allow or confirm.
+    #[must_use]
+    pub fn evaluate(&self, command: &str, cwd: &Path) -> PolicyDecision {
+        let tokens = tokenize_shell(command).tokens;
+        let Some(program) = tokens.first().map(|t| program_name(t)) else {
+            return PolicyDecision::Allow;
+        };
+
+        if self.deny_run.iter().any(|name| name == program) {
+            return PolicyDecision::Deny(format!("'{program}' is denied by policy"));
+        }
+        if !self.allow_run.is_empty() && !self.allow_run.iter().any(|name| name == program) {
+            return PolicyDecision::Deny(format!(
+                "'{program}' is not in the policy's allow_run list"
+            ));
+        }
+
+        for arg in tokens.iter().skip(1).filter(|arg| !arg.starts_with('-')) {
+            let path = resolve_path(cwd, arg);
+            if path_denied(&path, &self.deny_write, &self.allow_write)
+                || path_denied(&path, &self.deny_read, &self.allow_read)
+            {
+                return PolicyDecision::Deny(format!("'{}' is denied by policy", path.display()));
+            }
+        }
+
+        if self.confirm_run.iter().any(|name| name == program) {
+            return PolicyDecision::Ask(format!("'{program}' requires confirmation by policy"));
+        }
+
+        PolicyDecision::Allow
+    }
+}
+
+/// Resolve `raw` against `cwd` if it isn't already absolute, then normalize
+/// `.`/`..` components lexically - no filesystem access, so this works even
+/// for paths that don't exist yet (e.g. a file about to be created).
+fn resolve_path(cwd: &Path, raw: &str) -> PathBuf {
+    let candidate = Path::new(raw);
+    let absolute = if candidate.is_absolute() {
+        candidate.to_path_buf()
+    } else {
+        cwd.join(candidate)
+    };
+    normalize_lexically(&absolute)
+}
+
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Whether `path` is denied by the given entry lists: denied outright if
+/// any `deny` entry matches `path`, otherwise denied if `allow` is
+/// non-empty and no `allow` entry matches.
+fn path_denied(path: &Path, deny: &[String], allow: &[String]) -> bool {
+    if deny.iter().any(|entry| entry_matches(path, entry)) {
+        return true;
+    }
+    !allow.is_empty() && !allow.iter().any(|entry| entry_matches(path, entry))
+}
+
+/// Whether `path` is covered by a policy entry. An entry containing glob
+/// metacharacters (`*`, `?`, `[`) - the whole point of validating entries
+/// with [`is_valid_path_glob`] at `policy add` time - is matched with the
+/// `glob` crate's real pattern semantics, so `/etc/*.conf` behaves like an
+/// actual glob rather than a literal string: `*` and `?` never match a `/`,
+/// while `**` matches any number of directories. A plain entry (the common
+/// case - `/etc`, `/home/user/project`) has no pattern to expand, so it's
+/// treated as a directory prefix: it matches itself and everything under it.
+fn entry_matches(path: &Path, entry: &str) -> bool {
+    if entry.contains(['*', '?', '[']) {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..glob::MatchOptions::new()
+        };
+        glob::Pattern::new(entry).is_ok_and(|pattern| pattern.matches_path_with(path, options))
+    } else {
+        path.starts_with(entry)
+    }
+}
+
+/// Whether `pattern` compiles as a valid glob, so `policy add` can reject
+/// bad input before persisting it.
+#[must_use]
+pub fn is_valid_path_glob(pattern: &str) -> bool {
+    glob::Pattern::new(pattern).is_ok()
+}
+
+fn dirs_config_home() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_deny_rm() {
+        let policy = Policy::defaults();
+        let cwd = Path::new("/home/user/project");
+        assert_eq!(
+            policy.evaluate("rm -rf /tmp/test", cwd),
+            PolicyDecision::Deny("'rm' is denied by policy".to_string())
+        );
+    }
+
+    #[test]
+    fn test_defaults_allow_safe_command() {
+        let policy = Policy::defaults();
+        let cwd = Path::new("/home/user/project");
+        assert_eq!(policy.evaluate("ls -la", cwd), PolicyDecision::Allow);
+    }
+
+    #[test]
+    fn test_allow_run_denies_unlisted_executable() {
+        let policy = Policy {
+            allow_run: vec!["ls".to_string()],
+            ..Policy::default()
+        };
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("curl http://example.com", cwd),
+            PolicyDecision::Deny(_)
+        ));
+        assert_eq!(policy.evaluate("ls -la", cwd), PolicyDecision::Allow);
+    }
+
+    #[test]
+    fn test_confirm_run_asks_instead_of_denying() {
+        let policy = Policy {
+            confirm_run: vec!["curl".to_string()],
+            ..Policy::default()
+        };
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("curl http://example.com", cwd),
+            PolicyDecision::Ask(_)
+        ));
+    }
+
+    #[test]
+    fn test_deny_write_blocks_path_under_prefix() {
+        let policy = Policy {
+            deny_write: vec!["/etc".to_string()],
+            ..Policy::default()
+        };
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("tee /etc/passwd", cwd),
+            PolicyDecision::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn test_relative_path_resolved_against_cwd() {
+        let policy = Policy {
+            deny_write: vec!["/home/user/project/secrets".to_string()],
+            ..Policy::default()
+        };
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("cat secrets/key.pem", cwd),
+            PolicyDecision::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn test_allow_write_denies_path_not_covered() {
+        let policy = Policy {
+            allow_write: vec!["/home/user/project".to_string()],
+            ..Policy::default()
+        };
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("tee /etc/passwd", cwd),
+            PolicyDecision::Deny(_)
+        ));
+        assert_eq!(
+            policy.evaluate("tee ./notes.txt", cwd),
+            PolicyDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_deny_beats_allow_on_same_path() {
+        let policy = Policy {
+            allow_write: vec!["/home/user/project".to_string()],
+            deny_write: vec!["/home/user/project/secrets".to_string()],
+            ..Policy::default()
+        };
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("tee secrets/key.pem", cwd),
+            PolicyDecision::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn test_merged_with_defaults_keeps_user_rules_and_block_rm() {
+        let policy = Policy {
+            deny_run: vec!["curl".to_string()],
+            ..Policy::default()
+        }
+        .merged_with_defaults();
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("curl http://example.com", cwd),
+            PolicyDecision::Deny(_)
+        ));
+        assert!(matches!(
+            policy.evaluate("rm file.txt", cwd),
+            PolicyDecision::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn test_save_then_load_toml_roundtrips() {
+        let dir = std::env::temp_dir().join(format!(
+            "agent-hooks-policy-test-{}",
+            std::process::id()
+        ));
+        let path = dir.join("policy.toml");
+        let policy = Policy {
+            deny_run: vec!["curl".to_string()],
+            ..Policy::default()
+        };
+        policy.save(&path).unwrap();
+        let loaded = Policy::load(&path).unwrap();
+        assert_eq!(loaded.deny_run, vec!["curl".to_string()]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_save_then_load_json_roundtrips() {
+        let dir = std::env::temp_dir().join(format!(
+            "agent-hooks-policy-test-json-{}",
+            std::process::id()
+        ));
+        let path = dir.join("policy.json");
+        let policy = Policy {
+            deny_write: vec!["/etc".to_string()],
+            ..Policy::default()
+        };
+        policy.save(&path).unwrap();
+        let loaded = Policy::load(&path).unwrap();
+        assert_eq!(loaded.deny_write, vec!["/etc".to_string()]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_deny_write_glob_pattern_matches() {
+        let policy = Policy {
+            deny_write: vec!["/etc/*.conf".to_string()],
+            ..Policy::default()
+        };
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("tee /etc/app.conf", cwd),
+            PolicyDecision::Deny(_)
+        ));
+        assert_eq!(
+            policy.evaluate("tee /etc/subdir/app.conf", cwd),
+            PolicyDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_deny_write_glob_recursive_pattern_matches_nested_path() {
+        let policy = Policy {
+            deny_write: vec!["/etc/**".to_string()],
+            ..Policy::default()
+        };
+        let cwd = Path::new("/home/user/project");
+        assert!(matches!(
+            policy.evaluate("tee /etc/nested/app.conf", cwd),
+            PolicyDecision::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn test_is_valid_path_glob_accepts_valid_patterns() {
+        assert!(is_valid_path_glob("/etc/**"));
+        assert!(is_valid_path_glob("/home/user/*.key"));
+    }
+
+    #[test]
+    fn test_is_valid_path_glob_rejects_unclosed_bracket() {
+        assert!(!is_valid_path_glob("/etc/[abc"));
+    }
+
+    #[test]
+    fn test_detectors_includes_built_ins_by_default() {
+        let policy = Policy::default();
+        assert!(policy.detectors().iter().any(|d| d.name == "rm"));
+    }
+
+    #[test]
+    fn test_detectors_excludes_disabled_built_in() {
+        let policy = Policy {
+            disabled_detectors: vec!["mkfs".to_string()],
+            ..Policy::default()
+        };
+        assert!(!policy.detectors().iter().any(|d| d.name == "mkfs"));
+        assert!(policy.detectors().iter().any(|d| d.name == "rm"));
+    }
+
+    #[test]
+    fn test_detectors_includes_valid_custom_detector() {
+        let policy = Policy {
+            custom_detectors: vec![CustomDetector {
+                name: "curl-pipe-sh".to_string(),
+                pattern: r"curl\s+.*\|\s*sh".to_string(),
+                decision: CustomDetectorDecision::Ask,
+                description: "piping curl output into sh".to_string(),
+            }],
+            ..Policy::default()
+        };
+        assert!(policy.detectors().iter().any(|d| d.name == "curl-pipe-sh"));
+    }
+
+    #[test]
+    fn test_detectors_skips_custom_detector_with_invalid_pattern() {
+        let policy = Policy {
+            custom_detectors: vec![CustomDetector {
+                name: "bad".to_string(),
+                pattern: "[unclosed".to_string(),
+                decision: CustomDetectorDecision::Ask,
+                description: "broken".to_string(),
+            }],
+            ..Policy::default()
+        };
+        assert!(!policy.detectors().iter().any(|d| d.name == "bad"));
+    }
+}
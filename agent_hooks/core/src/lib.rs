@@ -5,6 +5,10 @@
 
 use regex::Regex;
 use std::sync::LazyLock;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Attribute, Meta, MetaList, Token};
 
 // ============================================================================
 // rm command detection
@@ -28,7 +32,213 @@ static RM_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
 /// Returns `true` if the command should be blocked.
 #[must_use]
 pub fn is_rm_command(cmd: &str) -> bool {
-    RM_PATTERN.is_match(cmd)
+    is_rm_command_at_depth(cmd, MAX_COMMAND_RECURSION_DEPTH)
+}
+
+fn is_rm_command_at_depth(cmd: &str, depth: u8) -> bool {
+    if RM_PATTERN.is_match(cmd) {
+        return true;
+    }
+    depth > 0 && for_each_inner_command(cmd, |inner| is_rm_command_at_depth(inner, depth - 1))
+}
+
+// ============================================================================
+// Shell tokenization
+// ============================================================================
+
+/// How many levels of wrapper/substitution unwrapping to chase before
+/// giving up - bounds recursion on adversarial input like nested
+/// `eval`/`$(...)` chains.
+const MAX_COMMAND_RECURSION_DEPTH: u8 = 4;
+
+#[cfg(not(windows))]
+const C_FLAG_WRAPPERS: &[(&str, &str)] = &[("bash", "-c"), ("sh", "-c"), ("zsh", "-c")];
+
+#[cfg(windows)]
+const C_FLAG_WRAPPERS: &[(&str, &str)] = &[("cmd", "/c"), ("powershell", "-command")];
+
+/// A lexed shell command: its top-level tokens (quotes resolved, whitespace
+/// split), plus every command-substitution's inner command string, found
+/// wherever it appears (not just as a whole token) so obfuscation like
+/// `$(echo rm) -rf /` is still visible to the detectors below.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShellTokens {
+    pub tokens: Vec<String>,
+    pub substitutions: Vec<String>,
+}
+
+/// Split `cmd` into shell-like tokens, honoring single/double quotes (so
+/// quoted whitespace doesn't split a token) and pulling out every backtick
+/// or `$(...)` command substitution's inner command string. An `$(echo
+/// ...)` substitution is additionally resolved to its literal output and
+/// spliced back into the surrounding token, since that's the one kind of
+/// substitution we can evaluate without actually running anything.
+#[must_use]
+pub fn tokenize_shell(cmd: &str) -> ShellTokens {
+    let chars: Vec<char> = cmd.chars().collect();
+    let mut tokens = Vec::new();
+    let mut substitutions = Vec::new();
+    let mut current = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                i += 1;
+            }
+            '\'' | '"' => {
+                let quote = chars[i];
+                i += 1;
+                while i < chars.len() && chars[i] != quote {
+                    current.push(chars[i]);
+                    i += 1;
+                }
+                i += 1;
+            }
+            '`' => {
+                i += 1;
+                let start = i;
+                while i < chars.len() && chars[i] != '`' {
+                    i += 1;
+                }
+                let inner: String = chars[start..i].iter().collect();
+                i += 1;
+                if let Some(resolved) = resolve_echo(&inner) {
+                    current.push_str(&resolved);
+                }
+                substitutions.push(inner);
+            }
+            '$' if chars.get(i + 1) == Some(&'(') => {
+                i += 2;
+                let start = i;
+                let mut depth = 1u32;
+                while i < chars.len() && depth > 0 {
+                    match chars[i] {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    if depth > 0 {
+                        i += 1;
+                    }
+                }
+                let inner: String = chars[start..i].iter().collect();
+                i += 1;
+                if let Some(resolved) = resolve_echo(&inner) {
+                    current.push_str(&resolved);
+                }
+                substitutions.push(inner);
+            }
+            c => {
+                current.push(c);
+                i += 1;
+            }
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    ShellTokens {
+        tokens,
+        substitutions,
+    }
+}
+
+/// A token's program name: the final path component, with (on Windows) a
+/// `.exe` suffix stripped, so `/bin/bash`, `bash`, and (on Windows)
+/// `bash.exe` all resolve the same way.
+#[must_use]
+pub fn program_name(token: &str) -> &str {
+    let base = token.rsplit(['/', '\\']).next().unwrap_or(token);
+    #[cfg(windows)]
+    {
+        base.strip_suffix(".exe").unwrap_or(base)
+    }
+    #[cfg(not(windows))]
+    {
+        base
+    }
+}
+
+/// If `inner` is an `echo` invocation (ignoring leading `-n`/`-e`-style
+/// flags), statically resolve what it would print to stdout - the only
+/// command substitution we can evaluate without running anything, and
+/// exactly the trick behind `$(echo rm) -rf /`-style obfuscation.
+fn resolve_echo(inner: &str) -> Option<String> {
+    let mut tokens = tokenize_shell(inner).tokens.into_iter();
+    if program_name(&tokens.next()?) != "echo" {
+        return None;
+    }
+    Some(
+        tokens
+            .filter(|arg| !arg.starts_with('-'))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Whether `token` is the flag that introduces a wrapper's command-string
+/// argument. Besides an exact match, a combined short-option cluster ending
+/// in `c` (`-lc`, `-ic`, ...) still means "run the next argument as a
+/// command", which real shells support for `-c`.
+fn flag_matches(token: &str, flag: &str) -> bool {
+    token.eq_ignore_ascii_case(flag)
+        || (flag == "-c" && token.starts_with('-') && !token.starts_with("--") && token.ends_with('c'))
+}
+
+/// If `tokens` starts with a known "run this string as a shell command"
+/// wrapper (`bash -c`, `sh -c`, `eval`, `xargs`, and on Windows `cmd /c`,
+/// `powershell -Command`), extract the command string it would run.
+fn wrapped_command(tokens: &[String]) -> Option<String> {
+    let first = tokens.first()?;
+    let name = program_name(first);
+
+    #[cfg(not(windows))]
+    {
+        if matches!(name, "eval" | "xargs") {
+            let rest = &tokens[1..];
+            return (!rest.is_empty()).then(|| rest.join(" "));
+        }
+    }
+
+    for (wrapper, flag) in C_FLAG_WRAPPERS {
+        if !name.eq_ignore_ascii_case(wrapper) {
+            continue;
+        }
+        let flag_pos = tokens[1..].iter().position(|t| flag_matches(t, flag))?;
+        return tokens.get(flag_pos + 2).cloned();
+    }
+
+    None
+}
+
+/// Tokenize `cmd` and run `check` against every inner command it could
+/// ultimately execute: a recognized wrapper's command-string argument, each
+/// raw command-substitution body, and (when a substitution changed the
+/// reconstructed command) the substitution-resolved command line itself.
+/// Stops at the first `check` that returns `true`.
+fn for_each_inner_command(cmd: &str, mut check: impl FnMut(&str) -> bool) -> bool {
+    let shell_tokens = tokenize_shell(cmd);
+
+    if let Some(inner) = wrapped_command(&shell_tokens.tokens) {
+        if check(&inner) {
+            return true;
+        }
+    }
+
+    if shell_tokens.substitutions.iter().any(|inner| check(inner)) {
+        return true;
+    }
+
+    if shell_tokens.substitutions.is_empty() {
+        return false;
+    }
+    let resolved = shell_tokens.tokens.join(" ");
+    resolved != cmd && check(&resolved)
 }
 
 // ============================================================================
@@ -74,20 +284,214 @@ static FIND_CHECK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\|").unwrap()
 #[must_use]
 #[expect(clippy::missing_panics_doc)]
 pub fn check_destructive_find(cmd: &str) -> Option<&'static str> {
-    if !FIND_CHECK.is_match(cmd) {
+    check_destructive_find_at_depth(cmd, MAX_COMMAND_RECURSION_DEPTH)
+}
+
+fn check_destructive_find_at_depth(cmd: &str, depth: u8) -> Option<&'static str> {
+    if let Some(description) = destructive_find_shallow(cmd) {
+        return Some(description);
+    }
+
+    if depth == 0 {
         return None;
     }
 
+    let mut found = None;
+    for_each_inner_command(cmd, |inner| {
+        found = check_destructive_find_at_depth(inner, depth - 1);
+        found.is_some()
+    });
+    found
+}
+
+#[expect(clippy::missing_panics_doc)]
+fn destructive_find_shallow(cmd: &str) -> Option<&'static str> {
+    if !FIND_CHECK.is_match(cmd) {
+        return None;
+    }
     for (pattern, description) in DESTRUCTIVE_PATTERNS {
         let re = Regex::new(&format!("(?i){pattern}")).unwrap();
         if re.is_match(cmd) {
             return Some(description);
         }
     }
-
     None
 }
 
+// ============================================================================
+// Pluggable danger scanner
+// ============================================================================
+
+/// What a matched [`Detector`] recommends doing with the command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DangerDecision {
+    Deny,
+    Ask,
+}
+
+/// A single check run over a command line by [`scan_command`]. Built-in
+/// detectors (see [`default_detectors`]) wrap the existing rm/find checks
+/// plus a handful of regex patterns for other destructive shells; callers
+/// can add more of their own via [`Detector::from_pattern`], e.g. ones
+/// compiled at runtime from a user-supplied policy file.
+#[derive(Clone)]
+pub struct Detector {
+    pub name: String,
+    pub decision: DangerDecision,
+    description: Option<String>,
+    check: DetectorCheck,
+}
+
+#[derive(Clone)]
+enum DetectorCheck {
+    /// A built-in check that may return its own description per match
+    /// (e.g. which destructive `find` pattern fired), overriding `description`.
+    Static(fn(&str) -> Option<&'static str>),
+    Pattern(Regex),
+}
+
+impl Detector {
+    fn built_in(
+        name: &'static str,
+        decision: DangerDecision,
+        check: fn(&str) -> Option<&'static str>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            decision,
+            description: None,
+            check: DetectorCheck::Static(check),
+        }
+    }
+
+    /// Build a detector from a regex `pattern`, matched case-insensitively
+    /// against the whole command line. Fails if `pattern` doesn't compile.
+    pub fn from_pattern(
+        name: impl Into<String>,
+        pattern: &str,
+        decision: DangerDecision,
+        description: impl Into<String>,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.into(),
+            decision,
+            description: Some(description.into()),
+            check: DetectorCheck::Pattern(Regex::new(&format!("(?i){pattern}"))?),
+        })
+    }
+
+    fn matches(&self, cmd: &str) -> Option<String> {
+        match &self.check {
+            DetectorCheck::Static(check) => check(cmd).map(str::to_string),
+            DetectorCheck::Pattern(re) => re
+                .is_match(cmd)
+                .then(|| self.description.clone().unwrap_or_default()),
+        }
+    }
+}
+
+/// The built-in detector list: today's rm and destructive-find checks, plus
+/// a handful of other commonly-destructive shells (`dd of=`, `mkfs`, `git
+/// clean -fdx`, `truncate -s 0`, recursive `chmod 777`). Order matters - the
+/// first match wins.
+///
+/// A `>` redirect detector used to live here too, matched as a raw regex
+/// over the untokenized command. In practice that fired on essentially any
+/// ordinary `cmd > file` redirect (`cargo build > build.log` and the like),
+/// which is routine rather than destructive - the opposite of what every
+/// other detector in this list targets (a specific destructive program or
+/// flag combination). It's been dropped rather than kept as a source of
+/// constant false-positive `Ask` friction.
+#[must_use]
+pub fn default_detectors() -> Vec<Detector> {
+    vec![
+        Detector::built_in("rm", DangerDecision::Deny, |cmd| {
+            RM_PATTERN
+                .is_match(cmd)
+                .then_some("rm (or equivalent) command")
+        }),
+        Detector::built_in(
+            "destructive-find",
+            DangerDecision::Ask,
+            destructive_find_shallow,
+        ),
+        Detector::from_pattern(
+            "dd",
+            r"(^|[;&|()]\s*)(sudo\s+)?dd\s+.*\bof=",
+            DangerDecision::Ask,
+            "dd writing directly to a device or file (of=)",
+        )
+        .expect("valid pattern"),
+        Detector::from_pattern(
+            "mkfs",
+            r"(^|[;&|()]\s*)(sudo\s+)?mkfs(\.\w+)?\s",
+            DangerDecision::Deny,
+            "mkfs formatting a filesystem",
+        )
+        .expect("valid pattern"),
+        Detector::from_pattern(
+            "git-clean",
+            r"git\s+clean\s+.*-[a-z]*f[a-z]*d",
+            DangerDecision::Ask,
+            "git clean -fdx removing untracked files",
+        )
+        .expect("valid pattern"),
+        Detector::from_pattern(
+            "truncate",
+            r"(^|[;&|()]\s*)truncate\s+.*-s\s*0\b",
+            DangerDecision::Ask,
+            "truncate -s 0 clobbering a file",
+        )
+        .expect("valid pattern"),
+        Detector::from_pattern(
+            "chmod-recursive",
+            r"(^|[;&|()]\s*)(sudo\s+)?chmod\s+-R\s+(0?777|a\+rwx)",
+            DangerDecision::Ask,
+            "chmod -R widening permissions recursively",
+        )
+        .expect("valid pattern"),
+    ]
+}
+
+/// A detector's verdict, as returned by [`scan_command`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DangerFinding {
+    pub detector: String,
+    pub decision: DangerDecision,
+    pub description: String,
+}
+
+/// Run `detectors`, in order, over `cmd` and every inner command it could
+/// ultimately execute (wrapped command strings, command substitutions),
+/// returning the first match.
+#[must_use]
+pub fn scan_command(cmd: &str, detectors: &[Detector]) -> Option<DangerFinding> {
+    scan_command_at_depth(cmd, detectors, MAX_COMMAND_RECURSION_DEPTH)
+}
+
+fn scan_command_at_depth(cmd: &str, detectors: &[Detector], depth: u8) -> Option<DangerFinding> {
+    for detector in detectors {
+        if let Some(description) = detector.matches(cmd) {
+            return Some(DangerFinding {
+                detector: detector.name.clone(),
+                decision: detector.decision,
+                description,
+            });
+        }
+    }
+
+    if depth == 0 {
+        return None;
+    }
+
+    let mut found = None;
+    for_each_inner_command(cmd, |inner| {
+        found = scan_command_at_depth(inner, detectors, depth - 1);
+        found.is_some()
+    });
+    found
+}
+
 // ============================================================================
 // Rust #[allow(...)] / #[expect(...)] detection
 // ============================================================================
@@ -98,75 +502,414 @@ static RUST_ALLOW_PATTERN: LazyLock<Regex> =
 static RUST_EXPECT_PATTERN: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"#!?\[expect\s*\(").unwrap());
 
-/// Check if a position in the content is inside a line comment or string literal.
+/// Lexer state used by `is_in_comment_or_string` to track exactly one token
+/// kind at a time as it walks the content byte by byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LexState {
+    Code,
+    LineComment,
+    BlockComment { depth: u32 },
+    Char,
+    Str,
+    RawStr { hashes: u32 },
+}
+
+/// Check if a position in the content is inside a line comment, a block
+/// comment, a char literal, or a string literal (including raw strings).
+///
+/// This walks `content` once as a real state machine rather than counting
+/// occurrences of `//`, `/*`, `*/`, and quote characters, which previously
+/// went wrong on a URL inside a string (`"http://..."` was mistaken for a
+/// line comment), nested block comments, and char literals like `'"'`
+/// desynchronizing the string scanner.
 fn is_in_comment_or_string(content: &str, match_start: usize) -> bool {
-    let before = &content[..match_start];
+    let bytes = content.as_bytes();
+    let mut state = LexState::Code;
+    let mut i = 0;
 
-    // Check if in line comment (// ...)
-    let line_start = before.rfind('\n').map_or(0, |p| p + 1);
-    let current_line = &before[line_start..];
-    if current_line.contains("//") {
-        return true;
+    while i < match_start && i < bytes.len() {
+        match state {
+            LexState::Code => {
+                if bytes[i..].starts_with(b"//") {
+                    state = LexState::LineComment;
+                    i += 2;
+                    continue;
+                }
+                if bytes[i..].starts_with(b"/*") {
+                    state = LexState::BlockComment { depth: 1 };
+                    i += 2;
+                    continue;
+                }
+                if bytes[i] == b'"' {
+                    state = LexState::Str;
+                    i += 1;
+                    continue;
+                }
+                if bytes[i] == b'\'' {
+                    // Only treat this as a char literal if it plausibly
+                    // closes within a few bytes (`'x'` or `'\''`), so a
+                    // lifetime or generic tick like `'a` doesn't eat the
+                    // rest of the file.
+                    if looks_like_char_literal(bytes, i) {
+                        state = LexState::Char;
+                    }
+                    i += 1;
+                    continue;
+                }
+                if let Some((hashes, prefix_len)) = raw_string_prefix_len(bytes, i) {
+                    state = LexState::RawStr { hashes };
+                    i += prefix_len;
+                    continue;
+                }
+                i += 1;
+            }
+            LexState::LineComment => {
+                if bytes[i] == b'\n' {
+                    state = LexState::Code;
+                }
+                i += 1;
+            }
+            LexState::BlockComment { depth } => {
+                if bytes[i..].starts_with(b"/*") {
+                    state = LexState::BlockComment { depth: depth + 1 };
+                    i += 2;
+                } else if bytes[i..].starts_with(b"*/") {
+                    state = if depth > 1 {
+                        LexState::BlockComment { depth: depth - 1 }
+                    } else {
+                        LexState::Code
+                    };
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            LexState::Char => {
+                if bytes[i] == b'\\' {
+                    i += 2;
+                } else if bytes[i] == b'\'' {
+                    state = LexState::Code;
+                    i += 1;
+                } else {
+                    i += 1;
+                }
+            }
+            LexState::Str => {
+                if bytes[i] == b'\\' {
+                    i += 2;
+                } else if bytes[i] == b'"' {
+                    state = LexState::Code;
+                    i += 1;
+                } else {
+                    i += 1;
+                }
+            }
+            LexState::RawStr { hashes } => {
+                if bytes[i] == b'"' && has_closing_hashes(bytes, i + 1, hashes) {
+                    state = LexState::Code;
+                    i += 1 + hashes as usize;
+                } else {
+                    i += 1;
+                }
+            }
+        }
     }
 
-    // Check if inside a block comment (/* ... */)
-    let block_open = before.matches("/*").count();
-    let block_close = before.matches("*/").count();
-    if block_open > block_close {
-        return true;
+    !matches!(state, LexState::Code)
+}
+
+/// `'x'`, `'\''`, `'\n'`, `'\\'` all plausibly close within 4 bytes; a bare
+/// `'a` generic/lifetime tick does not.
+fn looks_like_char_literal(bytes: &[u8], quote_pos: usize) -> bool {
+    let rest = &bytes[quote_pos + 1..];
+    if rest.first() == Some(&b'\\') {
+        return rest.iter().take(5).skip(1).any(|&b| b == b'\'');
     }
+    rest.len() >= 2 && rest[1] == b'\''
+}
+
+/// Recognize a raw string prefix (`r"`, `r#"`, `br"`, `br##"`, ...) starting
+/// at `pos`, returning the hash count and the total byte length of the
+/// prefix up to and including the opening quote.
+fn raw_string_prefix_len(bytes: &[u8], pos: usize) -> Option<(u32, usize)> {
+    if !matches!(bytes.get(pos), Some(&b'r') | Some(&b'b')) {
+        return None;
+    }
+    let mut j = pos + 1;
+    if bytes.get(pos) == Some(&b'b') {
+        if bytes.get(j) != Some(&b'r') {
+            return None;
+        }
+        j += 1;
+    }
+    let mut hashes = 0u32;
+    while bytes.get(j) == Some(&b'#') {
+        hashes += 1;
+        j += 1;
+    }
+    if bytes.get(j) == Some(&b'"') {
+        Some((hashes, j + 1 - pos))
+    } else {
+        None
+    }
+}
+
+fn has_closing_hashes(bytes: &[u8], pos: usize, hashes: u32) -> bool {
+    (0..hashes).all(|offset| bytes.get(pos + offset as usize) == Some(&b'#'))
+}
+
+/// Which attribute a detected lint came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllowKind {
+    /// Found via `#[allow(...)]` / `#![allow(...)]`.
+    Allow,
+    /// Found via `#[expect(...)]` / `#![expect(...)]`.
+    Expect,
+}
+
+/// One lint name suppressed by an `allow`/`expect` attribute, with the tool
+/// prefix preserved (`clippy::pedantic`, `rustc::...`) and the byte span of
+/// the attribute it came from, so callers can render precise messages or
+/// point an editor at the exact spot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowedLint {
+    pub lint: String,
+    pub kind: AllowKind,
+    pub span: (usize, usize),
+}
+
+/// Convert a 1-indexed line / 0-indexed char column, as reported by
+/// `proc_macro2::Span::start()`/`end()`, to a byte offset into `content`.
+fn line_col_to_byte_offset(content: &str, line: usize, column: usize) -> Option<usize> {
+    let mut offset = 0;
+    for (idx, line_str) in content.split('\n').enumerate() {
+        if idx + 1 == line {
+            let byte_col = line_str
+                .char_indices()
+                .nth(column)
+                .map_or(line_str.len(), |(b, _)| b);
+            return Some(offset + byte_col);
+        }
+        offset += line_str.len() + 1;
+    }
+    None
+}
+
+fn byte_span(content: &str, span: proc_macro2::Span) -> (usize, usize) {
+    let start = span.start();
+    let end = span.end();
+    let start_offset = line_col_to_byte_offset(content, start.line, start.column).unwrap_or(0);
+    let end_offset =
+        line_col_to_byte_offset(content, end.line, end.column).unwrap_or(start_offset);
+    (start_offset, end_offset)
+}
+
+fn path_to_string(path: &syn::Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Lint names in a `#[allow(a, b, reason = "...")]`-style list, skipping the
+/// `reason = "..."` justification (rustc's own accepted syntax for these
+/// attributes) since it isn't a lint name.
+fn lint_names_from_list(list: &MetaList) -> Vec<String> {
+    let Ok(nested) = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+        return Vec::new();
+    };
+    nested
+        .iter()
+        .filter_map(|meta| match meta {
+            Meta::Path(path) => Some(path_to_string(path)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Walks a parsed file's AST collecting every `allow`/`expect` attribute,
+/// including ones nested inside `#[cfg_attr(condition, allow(...))]`.
+struct AttrCollector<'a> {
+    content: &'a str,
+    lints: Vec<AllowedLint>,
+}
+
+impl<'a> Visit<'a> for AttrCollector<'a> {
+    fn visit_attribute(&mut self, attr: &'a Attribute) {
+        self.collect_from_attribute(attr);
+        syn::visit::visit_attribute(self, attr);
+    }
+}
+
+impl AttrCollector<'_> {
+    fn collect_from_attribute(&mut self, attr: &Attribute) {
+        let span = byte_span(self.content, attr.span());
+        if attr.path().is_ident("allow") {
+            self.push_from_meta(&attr.meta, AllowKind::Allow, span);
+        } else if attr.path().is_ident("expect") {
+            self.push_from_meta(&attr.meta, AllowKind::Expect, span);
+        } else if attr.path().is_ident("cfg_attr") {
+            self.collect_from_cfg_attr(attr, span);
+        }
+    }
+
+    fn push_from_meta(&mut self, meta: &Meta, kind: AllowKind, span: (usize, usize)) {
+        let Meta::List(list) = meta else { return };
+        for lint in lint_names_from_list(list) {
+            self.lints.push(AllowedLint { lint, kind, span });
+        }
+    }
+
+    fn collect_from_cfg_attr(&mut self, attr: &Attribute, span: (usize, usize)) {
+        let Ok(nested) =
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        else {
+            return;
+        };
+        // The first item is the cfg_attr's condition, e.g. `test` - skip it.
+        for meta in nested.iter().skip(1) {
+            if meta.path().is_ident("allow") {
+                self.push_from_meta(meta, AllowKind::Allow, span);
+            } else if meta.path().is_ident("expect") {
+                self.push_from_meta(meta, AllowKind::Expect, span);
+            }
+        }
+    }
+}
+
+fn collect_regex_lints(content: &str, pattern: &Regex, kind: AllowKind, out: &mut Vec<AllowedLint>) {
+    for m in pattern.find_iter(content) {
+        if is_in_comment_or_string(content, m.start()) {
+            continue;
+        }
+        let args_start = m.end();
+        let Some(args_end) = find_balanced_close_paren(content, args_start) else {
+            continue;
+        };
+        // Extend the span past the attribute's closing `]` when it follows.
+        let after_paren = args_end + 1;
+        let attr_end = content[after_paren..]
+            .trim_start()
+            .strip_prefix(']')
+            .map_or(after_paren, |rest| content.len() - rest.len());
+        for segment in split_top_level_commas(&content[args_start..args_end]) {
+            let Some(name) = lint_name_from_segment(segment) else {
+                continue;
+            };
+            out.push(AllowedLint {
+                lint: name.to_string(),
+                kind,
+                span: (m.start(), attr_end),
+            });
+        }
+    }
+}
+
+/// Find the byte offset of the `)` that closes the `(` implicitly opened at
+/// `args_start` (depth 1), skipping parens inside double-quoted string
+/// literals so a `reason = "see issue (#123)"` value can't desync the scan
+/// - unlike a plain `find(')')`, which would stop at that parenthesis
+/// inside the string.
+fn find_balanced_close_paren(content: &str, args_start: usize) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let mut depth = 1i32;
+    let mut in_string = false;
+    let mut i = args_start;
 
-    // Check if inside a string literal
-    let mut in_raw_string = false;
-    let mut i = 0;
-    let bytes = before.as_bytes();
     while i < bytes.len() {
-        if in_raw_string {
-            if bytes[i] == b'"' {
-                in_raw_string = false;
-            }
-        } else {
-            if bytes[i] == b'r' && i + 1 < bytes.len() {
-                let mut j = i + 1;
-                while j < bytes.len() && bytes[j] == b'#' {
-                    j += 1;
-                }
-                if j < bytes.len() && bytes[j] == b'"' {
-                    in_raw_string = true;
-                    i = j + 1;
-                    continue;
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'(' if !in_string => depth += 1,
+            b')' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
                 }
             }
-            if bytes[i] == b'"' && (i == 0 || bytes[i - 1] != b'\\') {
-                let mut k = i + 1;
-                while k < bytes.len() {
-                    if bytes[k] == b'"' && bytes[k - 1] != b'\\' {
-                        break;
-                    }
-                    k += 1;
-                }
-                if k >= bytes.len() {
-                    return true;
-                }
-                i = k + 1;
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split `args` on top-level commas, i.e. commas not nested inside a
+/// further `(...)` group or a string literal, so a `reason = "a, b"` item
+/// isn't torn apart into separate segments.
+fn split_top_level_commas(args: &str) -> Vec<&str> {
+    let bytes = args.as_bytes();
+    let mut segments = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => {
+                i += 2;
                 continue;
             }
+            b'"' => in_string = !in_string,
+            b'(' if !in_string => depth += 1,
+            b')' if !in_string => depth -= 1,
+            b',' if !in_string && depth == 0 => {
+                segments.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
         }
         i += 1;
     }
+    segments.push(args[start..].trim());
+    segments
+}
+
+/// A top-level segment of an `#[allow(...)]`/`#[expect(...)]` argument list
+/// is a lint name only if it's a bare path (`dead_code`, `clippy::all`) -
+/// mirroring the AST path's `Meta::Path` filter in [`lint_names_from_list`].
+/// `reason = "..."` and other key/value or nested-list items aren't lint
+/// names and must not be reported as one.
+fn lint_name_from_segment(segment: &str) -> Option<&str> {
+    let segment = segment.trim();
+    if segment.is_empty() || segment.contains('=') || segment.contains('(') {
+        return None;
+    }
+    Some(segment)
+}
 
-    in_raw_string
+/// Regex/lexer-based fallback used when `content` isn't a complete,
+/// syntactically valid file (e.g. a partial Edit snippet mid-file). Unlike
+/// the AST walk this doesn't unwrap `cfg_attr`, matching the scanner's
+/// historical behavior.
+fn find_allowed_lints_via_regex(content: &str) -> Vec<AllowedLint> {
+    let mut lints = Vec::new();
+    collect_regex_lints(content, &RUST_ALLOW_PATTERN, AllowKind::Allow, &mut lints);
+    collect_regex_lints(content, &RUST_EXPECT_PATTERN, AllowKind::Expect, &mut lints);
+    lints
 }
 
-/// Find if there are real matches of a pattern (not in comments or strings).
-#[inline]
-fn find_real_matches(content: &str, pattern: &Regex) -> bool {
-    for m in pattern.find_iter(content) {
-        if !is_in_comment_or_string(content, m.start()) {
-            return true;
-        }
+/// Find every lint suppressed by an `allow`/`expect` attribute in `content`,
+/// preserving tool prefixes (`clippy::`, `rustc::`) and unwrapping
+/// `#[cfg_attr(condition, allow(...))]`.
+///
+/// Parses `content` as a complete file with `syn` so `cfg_attr` nesting and
+/// attribute spans are exact; falls back to a simpler regex/lexer scan when
+/// `content` isn't a parseable complete file.
+#[must_use]
+pub fn find_allowed_lints(content: &str) -> Vec<AllowedLint> {
+    if let Ok(file) = syn::parse_file(content) {
+        let mut collector = AttrCollector {
+            content,
+            lints: Vec::new(),
+        };
+        collector.visit_file(&file);
+        return collector.lints;
     }
-    false
+
+    find_allowed_lints_via_regex(content)
 }
 
 /// Result of checking for Rust allow/expect attributes.
@@ -184,12 +927,15 @@ pub enum RustAllowCheckResult {
 
 /// Check if content contains #[allow(...)] or #[expect(...)] attributes.
 ///
-/// This function ignores attributes in comments and string literals.
-/// It does NOT check if the file is a Rust file - the caller should do that.
+/// This function ignores attributes in comments and string literals. It
+/// does NOT check if the file is a Rust file - the caller should do that.
+/// For the exact lint names and spans behind this summary, see
+/// [`find_allowed_lints`].
 #[must_use]
 pub fn check_rust_allow_attributes(content: &str) -> RustAllowCheckResult {
-    let has_allow = find_real_matches(content, &RUST_ALLOW_PATTERN);
-    let has_expect = find_real_matches(content, &RUST_EXPECT_PATTERN);
+    let lints = find_allowed_lints(content);
+    let has_allow = lints.iter().any(|lint| lint.kind == AllowKind::Allow);
+    let has_expect = lints.iter().any(|lint| lint.kind == AllowKind::Expect);
 
     match (has_allow, has_expect) {
         (true, true) => RustAllowCheckResult::HasBoth,
@@ -199,6 +945,143 @@ pub fn check_rust_allow_attributes(content: &str) -> RustAllowCheckResult {
     }
 }
 
+/// Options controlling which lints `disallowed_lints` treats as acceptable,
+/// modeled on rustc's per-lint session config: each lint carries its own
+/// level rather than one coarse allow/deny for the whole file.
+#[derive(Debug, Clone, Default)]
+pub struct DenyRustAllowOptions {
+    /// Lint names, or glob-ish prefixes like `clippy::*`, that may be
+    /// allowed/expected without being flagged. A lint absent from this list
+    /// is denied - leave empty to deny every `allow`/`expect` attribute.
+    pub allow_lints: Vec<String>,
+    /// Lint names, or glob-ish prefixes, that are always flagged even if
+    /// `allow_lints` would otherwise cover them. Takes precedence over
+    /// `allow_lints` when both match the same lint.
+    pub deny_lints: Vec<String>,
+}
+
+/// Whether `lint` is covered by `lints`. A pattern ending in `*` matches any
+/// lint starting with the part before it (`clippy::*` covers every clippy
+/// lint); anything else must match exactly.
+fn lint_matches_any(lint: &str, lints: &[String]) -> bool {
+    lints.iter().any(|pattern| {
+        pattern
+            .strip_suffix('*')
+            .map_or(lint == pattern, |prefix| lint.starts_with(prefix))
+    })
+}
+
+/// Every lint suppressed in `content` that `options` denies: either matched
+/// by `deny_lints`, or absent from `allow_lints`. `deny_lints` wins when a
+/// lint matches both lists. Lets the caller decide per-lint rather than
+/// per-attribute and name the specific offending lint in its denial message.
+#[must_use]
+pub fn disallowed_lints(content: &str, options: &DenyRustAllowOptions) -> Vec<AllowedLint> {
+    find_allowed_lints(content)
+        .into_iter()
+        .filter(|lint| {
+            lint_matches_any(&lint.lint, &options.deny_lints)
+                || !lint_matches_any(&lint.lint, &options.allow_lints)
+        })
+        .collect()
+}
+
+/// A machine-actionable fix for a denied attribute, modeled on
+/// rustfix/compiletest's suggested-replacement format: a byte span to
+/// replace and the text to replace it with, so a cooperating agent can
+/// apply the correction without re-parsing the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuggestedEdit {
+    pub span: (usize, usize),
+    pub replacement: String,
+}
+
+/// Byte span of the full line containing byte offset `pos`, including its
+/// trailing newline (if any) so deleting it doesn't leave a blank line.
+fn line_bounds(content: &str, pos: usize) -> (usize, usize) {
+    let line_start = content[..pos].rfind('\n').map_or(0, |idx| idx + 1);
+    let line_end = content[pos..]
+        .find('\n')
+        .map_or(content.len(), |idx| pos + idx + 1);
+    (line_start, line_end)
+}
+
+/// Suggest a fix for `lint`: delete the whole line its attribute is on, or,
+/// when `rewrite_to_expect` is set, rewrite `#[allow(...)]`/`#![allow(...)]`
+/// to `#[expect(...)]`/`#![expect(...)]` in place.
+#[must_use]
+pub fn suggest_edit_for(content: &str, lint: &AllowedLint, rewrite_to_expect: bool) -> SuggestedEdit {
+    if rewrite_to_expect {
+        let (start, end) = lint.span;
+        let replacement = content[start..end].replacen("allow", "expect", 1);
+        return SuggestedEdit {
+            span: lint.span,
+            replacement,
+        };
+    }
+
+    let span = line_bounds(content, lint.span.0);
+    SuggestedEdit {
+        span,
+        replacement: String::new(),
+    }
+}
+
+/// 1-indexed line numbers in `new_content` that were introduced by this
+/// edit, via a multiset line diff: each line in `old_content` can only
+/// cancel out one matching occurrence in `new_content`, so a line that
+/// appears more times in `new_content` than in `old_content` has its extra
+/// occurrences counted as new even when the line's text duplicates one
+/// already present (e.g. a second `#[allow(dead_code)]` added alongside an
+/// existing one). A plain set-membership check would hide that second
+/// occurrence behind the first's text.
+fn added_line_numbers(old_content: &str, new_content: &str) -> std::collections::HashSet<usize> {
+    let mut old_line_counts: std::collections::HashMap<&str, usize> =
+        std::collections::HashMap::new();
+    for line in old_content.lines() {
+        *old_line_counts.entry(line).or_insert(0) += 1;
+    }
+
+    let mut added = std::collections::HashSet::new();
+    for (idx, line) in new_content.lines().enumerate() {
+        match old_line_counts.get_mut(line) {
+            Some(remaining) if *remaining > 0 => *remaining -= 1,
+            _ => {
+                added.insert(idx + 1);
+            }
+        }
+    }
+    added
+}
+
+/// 1-indexed line number containing byte offset `pos`.
+fn line_number(content: &str, pos: usize) -> usize {
+    content[..pos].matches('\n').count() + 1
+}
+
+/// Every disallowed lint in `new_content` whose attribute is newly
+/// introduced by this edit - added or newly uncommented - rather than
+/// merely moved or left untouched from `old_content`.
+///
+/// Models this on the line diff compiletest does between expected and
+/// actual output: a line present verbatim in `old_content` is never "new",
+/// even if it now sits at a different offset. Unlike a plain set-membership
+/// check, this is a multiset diff - see [`added_line_numbers`] - so a
+/// second, identically-worded attribute added alongside a pre-existing one
+/// is still flagged.
+#[must_use]
+pub fn newly_disallowed_lints(
+    old_content: &str,
+    new_content: &str,
+    options: &DenyRustAllowOptions,
+) -> Vec<AllowedLint> {
+    let added_lines = added_line_numbers(old_content, new_content);
+    disallowed_lints(new_content, options)
+        .into_iter()
+        .filter(|lint| added_lines.contains(&line_number(new_content, lint.span.0)))
+        .collect()
+}
+
 /// Check if a file path is a Rust file.
 #[must_use]
 pub fn is_rust_file(file_path: &str) -> bool {
@@ -36,6 +36,44 @@ fn test_is_in_comment_or_string_after_comment() {
     assert!(!is_in_comment_or_string(content, 11));
 }
 
+#[test]
+fn test_is_in_comment_or_string_nested_block_comment() {
+    let content = "/* outer /* inner */ #[allow(dead_code)] */";
+    assert!(is_in_comment_or_string(content, 22));
+}
+
+#[test]
+fn test_is_in_comment_or_string_url_in_string_is_not_a_comment() {
+    let content = "let url = \"http://example.com\"; #[allow(dead_code)]";
+    assert!(!is_in_comment_or_string(
+        content,
+        content.find("#[allow").unwrap()
+    ));
+}
+
+#[test]
+fn test_is_in_comment_or_string_char_literal_quote_does_not_desync_strings() {
+    let content = "let c = '\"'; #[allow(dead_code)]";
+    assert!(!is_in_comment_or_string(
+        content,
+        content.find("#[allow").unwrap()
+    ));
+}
+
+#[test]
+fn test_is_in_comment_or_string_raw_string_with_double_hash() {
+    let content = "let s = r##\"#[allow(dead_code)]\"##; #[allow(dead_code)]";
+    let second = content.rfind("#[allow").unwrap();
+    assert!(!is_in_comment_or_string(content, second));
+}
+
+#[test]
+fn test_is_in_comment_or_string_byte_raw_string() {
+    let content = "let s = br#\"#[allow(dead_code)]\"#; #[allow(dead_code)]";
+    let second = content.rfind("#[allow").unwrap();
+    assert!(!is_in_comment_or_string(content, second));
+}
+
 // -------------------------------------------------------------------------
 // is_rm_command tests
 // -------------------------------------------------------------------------
@@ -72,6 +110,92 @@ fn test_is_rm_command_allows_grep_rm() {
     assert!(!is_rm_command("rma -rm"));
 }
 
+// -------------------------------------------------------------------------
+// Obfuscated destructive command tests (Unix only)
+// -------------------------------------------------------------------------
+
+#[cfg(not(windows))]
+#[test]
+fn test_is_rm_command_catches_echo_command_substitution() {
+    assert!(is_rm_command("$(echo rm) -rf /"));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_is_rm_command_catches_backtick_substitution() {
+    assert!(is_rm_command("`rm` file"));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_is_rm_command_catches_bash_c_wrapper() {
+    assert!(is_rm_command("bash -c \"rm -rf /\""));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_is_rm_command_catches_eval_wrapper() {
+    assert!(is_rm_command("eval \"rm x\""));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_is_rm_command_catches_xargs_wrapper() {
+    assert!(is_rm_command("xargs rm"));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_check_destructive_find_catches_combined_login_command_flag() {
+    let result = check_destructive_find("sh -lc 'find . -delete'");
+    assert!(result.is_some());
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_is_rm_command_does_not_flag_harmless_substitution() {
+    assert!(!is_rm_command("echo $(echo hello)"));
+}
+
+// -------------------------------------------------------------------------
+// Obfuscated destructive command tests (Windows only)
+// -------------------------------------------------------------------------
+
+#[cfg(windows)]
+#[test]
+fn test_is_rm_command_catches_cmd_c_wrapper() {
+    assert!(is_rm_command("cmd /c \"del file\""));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_is_rm_command_catches_powershell_command_wrapper() {
+    assert!(is_rm_command("powershell -Command \"Remove-Item file\""));
+}
+
+// -------------------------------------------------------------------------
+// tokenize_shell tests
+// -------------------------------------------------------------------------
+
+#[test]
+fn test_tokenize_shell_splits_on_whitespace() {
+    let result = tokenize_shell("rm -rf /tmp");
+    assert_eq!(result.tokens, vec!["rm", "-rf", "/tmp"]);
+    assert!(result.substitutions.is_empty());
+}
+
+#[test]
+fn test_tokenize_shell_keeps_quoted_whitespace_together() {
+    let result = tokenize_shell("bash -c \"rm -rf /\"");
+    assert_eq!(result.tokens, vec!["bash", "-c", "rm -rf /"]);
+}
+
+#[test]
+fn test_tokenize_shell_extracts_command_substitution_body() {
+    let result = tokenize_shell("echo $(rm -rf /)");
+    assert_eq!(result.substitutions, vec!["rm -rf /"]);
+}
+
 // -------------------------------------------------------------------------
 // check_destructive_find tests (Unix only)
 // -------------------------------------------------------------------------
@@ -123,6 +247,72 @@ fn test_check_destructive_find_safe() {
     assert!(check_destructive_find("Get-ChildItem").is_none());
 }
 
+// -------------------------------------------------------------------------
+// scan_command / default_detectors tests
+// -------------------------------------------------------------------------
+
+#[cfg(not(windows))]
+#[test]
+fn test_scan_command_flags_rm() {
+    let finding = scan_command("rm -rf /tmp/test", &default_detectors()).unwrap();
+    assert_eq!(finding.detector, "rm");
+    assert_eq!(finding.decision, DangerDecision::Deny);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_scan_command_flags_dd_of() {
+    let finding = scan_command("dd if=/dev/zero of=/dev/sda", &default_detectors()).unwrap();
+    assert_eq!(finding.detector, "dd");
+    assert_eq!(finding.decision, DangerDecision::Ask);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_scan_command_flags_git_clean_fdx() {
+    let finding = scan_command("git clean -fdx", &default_detectors()).unwrap();
+    assert_eq!(finding.detector, "git-clean");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_scan_command_flags_chmod_recursive() {
+    let finding = scan_command("chmod -R 777 .", &default_detectors()).unwrap();
+    assert_eq!(finding.detector, "chmod-recursive");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_scan_command_ignores_append_redirect() {
+    assert!(scan_command("echo hi >> log.txt", &default_detectors()).is_none());
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_scan_command_safe_command_is_none() {
+    assert!(scan_command("ls -la", &default_detectors()).is_none());
+}
+
+#[test]
+fn test_scan_command_runs_through_wrapped_commands() {
+    let finding = scan_command("bash -c 'rm -rf /'", &default_detectors()).unwrap();
+    assert_eq!(finding.detector, "rm");
+}
+
+#[test]
+fn test_scan_command_custom_pattern_detector() {
+    let detectors = vec![Detector::from_pattern(
+        "curl-pipe-sh",
+        r"curl\s+.*\|\s*sh",
+        DangerDecision::Ask,
+        "piping curl output into sh",
+    )
+    .unwrap()];
+    let finding = scan_command("curl https://example.com | sh", &detectors).unwrap();
+    assert_eq!(finding.detector, "curl-pipe-sh");
+    assert_eq!(finding.description, "piping curl output into sh");
+}
+
 // -------------------------------------------------------------------------
 // check_rust_allow_attributes tests
 // -------------------------------------------------------------------------
@@ -175,6 +365,237 @@ fn test_check_rust_allow_after_comment() {
     assert_eq!(result, RustAllowCheckResult::HasAllow);
 }
 
+// -------------------------------------------------------------------------
+// find_allowed_lints tests
+// -------------------------------------------------------------------------
+
+#[test]
+fn test_find_allowed_lints_simple_allow() {
+    let lints = find_allowed_lints("#[allow(dead_code)]\nfn foo() {}");
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].lint, "dead_code");
+    assert_eq!(lints[0].kind, AllowKind::Allow);
+}
+
+#[test]
+fn test_find_allowed_lints_preserves_clippy_prefix() {
+    let lints = find_allowed_lints("#[allow(clippy::pedantic)]\nfn foo() {}");
+    assert_eq!(lints[0].lint, "clippy::pedantic");
+}
+
+#[test]
+fn test_find_allowed_lints_multiple_lints_in_one_attribute() {
+    let lints = find_allowed_lints("#[allow(dead_code, unused)]\nfn foo() {}");
+    let names: Vec<_> = lints.iter().map(|lint| lint.lint.as_str()).collect();
+    assert_eq!(names, vec!["dead_code", "unused"]);
+}
+
+#[test]
+fn test_find_allowed_lints_ignores_reason_argument() {
+    let lints = find_allowed_lints("#[expect(dead_code, reason = \"cleaned up later\")]\nfn foo() {}");
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].lint, "dead_code");
+    assert_eq!(lints[0].kind, AllowKind::Expect);
+}
+
+#[test]
+fn test_find_allowed_lints_unwraps_cfg_attr() {
+    let lints = find_allowed_lints("#[cfg_attr(test, allow(dead_code))]\nfn foo() {}");
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].lint, "dead_code");
+    assert_eq!(lints[0].kind, AllowKind::Allow);
+}
+
+#[test]
+fn test_find_allowed_lints_inner_attribute() {
+    let lints = find_allowed_lints("#![allow(unused)]");
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].lint, "unused");
+}
+
+#[test]
+fn test_find_allowed_lints_falls_back_to_regex_for_partial_content() {
+    // Not a parseable complete file (a bare outer attribute with no item
+    // attached), but still a real #[allow(...)] an editor would introduce
+    // mid-file.
+    let lints = find_allowed_lints("// comment\n#[allow(dead_code)]");
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].lint, "dead_code");
+}
+
+#[test]
+fn test_find_allowed_lints_fallback_ignores_reason_argument() {
+    // Forces the regex fallback (no item attached, so `syn::parse_file`
+    // rejects it), with a `)` inside the reason string that a naive
+    // `find(')')` would mistake for the end of the argument list.
+    let lints =
+        find_allowed_lints("// comment\n#[expect(clippy::all, reason = \"see issue (#123)\")]");
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].lint, "clippy::all");
+    assert_eq!(lints[0].kind, AllowKind::Expect);
+}
+
+#[test]
+fn test_find_allowed_lints_fallback_ignores_string_literals() {
+    let lints = find_allowed_lints("let s = \"#[allow(dead_code)]\";");
+    assert!(lints.is_empty());
+}
+
+#[test]
+fn test_find_allowed_lints_span_covers_the_attribute() {
+    let content = "#[allow(dead_code)]\nfn foo() {}";
+    let lints = find_allowed_lints(content);
+    let (start, end) = lints[0].span;
+    assert_eq!(&content[start..end], "#[allow(dead_code)]");
+}
+
+// -------------------------------------------------------------------------
+// disallowed_lints tests
+// -------------------------------------------------------------------------
+
+#[test]
+fn test_disallowed_lints_flags_unpermitted_lint() {
+    let options = DenyRustAllowOptions::default();
+    let disallowed = disallowed_lints("#[allow(dead_code)]\nfn foo() {}", &options);
+    assert_eq!(disallowed.len(), 1);
+    assert_eq!(disallowed[0].lint, "dead_code");
+}
+
+#[test]
+fn test_disallowed_lints_permits_exact_match() {
+    let options = DenyRustAllowOptions {
+        allow_lints: vec!["dead_code".to_string()],
+        deny_lints: vec![],
+    };
+    let disallowed = disallowed_lints("#[allow(dead_code)]\nfn foo() {}", &options);
+    assert!(disallowed.is_empty());
+}
+
+#[test]
+fn test_disallowed_lints_permits_glob_prefix() {
+    let options = DenyRustAllowOptions {
+        allow_lints: vec!["clippy::*".to_string()],
+        deny_lints: vec![],
+    };
+    let disallowed = disallowed_lints("#[allow(clippy::pedantic)]\nfn foo() {}", &options);
+    assert!(disallowed.is_empty());
+}
+
+#[test]
+fn test_disallowed_lints_is_per_lint_not_per_attribute() {
+    let options = DenyRustAllowOptions {
+        allow_lints: vec!["dead_code".to_string()],
+        deny_lints: vec![],
+    };
+    let disallowed = disallowed_lints("#[allow(dead_code, unused)]\nfn foo() {}", &options);
+    assert_eq!(disallowed.len(), 1);
+    assert_eq!(disallowed[0].lint, "unused");
+}
+
+#[test]
+fn test_disallowed_lints_deny_wins_over_allow() {
+    let options = DenyRustAllowOptions {
+        allow_lints: vec!["clippy::*".to_string()],
+        deny_lints: vec!["clippy::all".to_string()],
+    };
+    let disallowed = disallowed_lints(
+        "#[allow(clippy::all, clippy::pedantic)]\nfn foo() {}",
+        &options,
+    );
+    assert_eq!(disallowed.len(), 1);
+    assert_eq!(disallowed[0].lint, "clippy::all");
+}
+
+#[test]
+fn test_disallowed_lints_deny_list_alone_still_denies_everything_else() {
+    let options = DenyRustAllowOptions {
+        allow_lints: vec![],
+        deny_lints: vec!["unsafe_code".to_string()],
+    };
+    let disallowed = disallowed_lints("#[allow(unsafe_code, dead_code)]\nfn foo() {}", &options);
+    assert_eq!(disallowed.len(), 2);
+}
+
+// -------------------------------------------------------------------------
+// suggest_edit_for tests
+// -------------------------------------------------------------------------
+
+#[test]
+fn test_suggest_edit_for_deletes_the_whole_line() {
+    let content = "#[allow(dead_code)]\nfn foo() {}\n";
+    let lint = &find_allowed_lints(content)[0];
+    let edit = suggest_edit_for(content, lint, false);
+    let (start, end) = edit.span;
+    assert_eq!(&content[start..end], "#[allow(dead_code)]\n");
+    assert_eq!(edit.replacement, "");
+}
+
+#[test]
+fn test_suggest_edit_for_rewrites_allow_to_expect() {
+    let content = "#[allow(dead_code)]\nfn foo() {}\n";
+    let lint = &find_allowed_lints(content)[0];
+    let edit = suggest_edit_for(content, lint, true);
+    assert_eq!(edit.replacement, "#[expect(dead_code)]");
+}
+
+#[test]
+fn test_suggest_edit_for_inner_attribute_deletes_its_own_line() {
+    let content = "#![allow(unused)]\nfn foo() {}\n";
+    let lint = &find_allowed_lints(content)[0];
+    let edit = suggest_edit_for(content, lint, false);
+    let (start, end) = edit.span;
+    assert_eq!(&content[start..end], "#![allow(unused)]\n");
+}
+
+// -------------------------------------------------------------------------
+// newly_disallowed_lints tests
+// -------------------------------------------------------------------------
+
+#[test]
+fn test_newly_disallowed_lints_flags_allow_added_by_this_edit() {
+    let old = "fn foo() {}\n";
+    let new = "#[allow(dead_code)]\nfn foo() {}\n";
+    let found = newly_disallowed_lints(old, new, &DenyRustAllowOptions::default());
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].lint, "dead_code");
+}
+
+#[test]
+fn test_newly_disallowed_lints_ignores_allow_that_already_existed() {
+    let old = "#[allow(dead_code)]\nfn foo() {}\n";
+    let new = "#[allow(dead_code)]\nfn foo() {}\nfn bar() {}\n";
+    let found = newly_disallowed_lints(old, new, &DenyRustAllowOptions::default());
+    assert!(found.is_empty());
+}
+
+#[test]
+fn test_newly_disallowed_lints_ignores_allow_that_merely_moved() {
+    let old = "#[allow(dead_code)]\nfn foo() {}\nfn bar() {}\n";
+    let new = "fn bar() {}\n#[allow(dead_code)]\nfn foo() {}\n";
+    let found = newly_disallowed_lints(old, new, &DenyRustAllowOptions::default());
+    assert!(found.is_empty());
+}
+
+#[test]
+fn test_newly_disallowed_lints_flags_newly_uncommented_allow() {
+    let old = "// #[allow(dead_code)]\nfn foo() {}\n";
+    let new = "#[allow(dead_code)]\nfn foo() {}\n";
+    let found = newly_disallowed_lints(old, new, &DenyRustAllowOptions::default());
+    assert_eq!(found.len(), 1);
+}
+
+#[test]
+fn test_newly_disallowed_lints_flags_second_allow_with_same_text_as_pre_existing_one() {
+    // `old` already has one #[allow(dead_code)]; `new` carries it through
+    // unchanged but adds a second, identically-worded #[allow(dead_code)]
+    // elsewhere. A set-membership diff would hide the new one behind the
+    // pre-existing line's text; the multiset diff must still flag it.
+    let old = "#[allow(dead_code)]\nfn foo() {}\n";
+    let new = "#[allow(dead_code)]\nfn foo() {}\n#[allow(dead_code)]\nfn bar() {}\n";
+    let found = newly_disallowed_lints(old, new, &DenyRustAllowOptions::default());
+    assert_eq!(found.len(), 1);
+}
+
 // -------------------------------------------------------------------------
 // is_rust_file tests
 // -------------------------------------------------------------------------
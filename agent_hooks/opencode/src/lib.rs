@@ -5,8 +5,8 @@
 #![expect(clippy::needless_pass_by_value)]
 
 use agent_hooks::{
-    RustAllowCheckResult, check_destructive_find, check_rust_allow_attributes, is_rm_command,
-    is_rust_file,
+    check_destructive_find, check_rust_allow_attributes, disallowed_lints, is_rm_command,
+    is_rust_file, suggest_edit_for, DenyRustAllowOptions, RustAllowCheckResult,
 };
 use napi_derive::napi;
 
@@ -60,3 +60,72 @@ pub fn check_rust_allow_attributes_js(content: String) -> RustAllowCheck {
         RustAllowCheckResult::HasBoth => RustAllowCheck::HasBoth,
     }
 }
+
+/// Find every lint suppressed in `content` (by `#[allow(...)]`/`#[expect(...)]`,
+/// including ones nested inside `cfg_attr`) that `allow_lints`/`deny_lints`
+/// deny.
+///
+/// Entries in either list may be exact lint names or glob-ish prefixes ending
+/// in `*`, e.g. `clippy::*` covers every clippy lint. A lint absent from
+/// `allow_lints` is denied; `deny_lints` always wins over `allow_lints` when
+/// both match. Returns the disallowed lint names, tool-prefixed where
+/// applicable (`clippy::pedantic`).
+#[napi(js_name = "findDisallowedLints")]
+#[must_use]
+pub fn find_disallowed_lints_js(
+    content: String,
+    allow_lints: Vec<String>,
+    deny_lints: Vec<String>,
+) -> Vec<String> {
+    disallowed_lints(
+        &content,
+        &DenyRustAllowOptions {
+            allow_lints,
+            deny_lints,
+        },
+    )
+    .into_iter()
+    .map(|lint| lint.lint)
+    .collect()
+}
+
+/// A rustfix-style suggested fix: a byte span (as a `[start, end)` pair,
+/// directly indexable into the `content` string without re-parsing) and the
+/// text to replace it with.
+#[napi(object)]
+pub struct SuggestedEdit {
+    pub start: u32,
+    pub end: u32,
+    pub replacement: String,
+}
+
+/// Suggest a fix for the first disallowed lint in `content`: delete the
+/// whole line its attribute is on, or, when `rewrite_to_expect` is set,
+/// rewrite `#[allow(...)]` to `#[expect(...)]` in place.
+///
+/// Returns `null` if nothing in `content` is disallowed.
+#[napi(js_name = "suggestRustAllowEdit")]
+#[must_use]
+pub fn suggest_rust_allow_edit_js(
+    content: String,
+    allow_lints: Vec<String>,
+    deny_lints: Vec<String>,
+    rewrite_to_expect: bool,
+) -> Option<SuggestedEdit> {
+    let disallowed = disallowed_lints(
+        &content,
+        &DenyRustAllowOptions {
+            allow_lints,
+            deny_lints,
+        },
+    );
+    let lint = disallowed.first()?;
+    let edit = suggest_edit_for(&content, lint, rewrite_to_expect);
+    let start = u32::try_from(edit.span.0).unwrap_or(u32::MAX);
+    let end = u32::try_from(edit.span.1).unwrap_or(u32::MAX);
+    Some(SuggestedEdit {
+        start,
+        end,
+        replacement: edit.replacement,
+    })
+}
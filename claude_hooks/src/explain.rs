@@ -0,0 +1,192 @@
+//! `explain`: run a command or file edit through every registered hook and
+//! report which rule(s) matched, without requiring a Claude Code session or
+//! a JSON payload on stdin. This gives rule authors a fast offline loop -
+//! `claude_hooks explain "find . -delete"` - and gives users an auditable
+//! answer to "why was that blocked?".
+
+use serde::Serialize;
+
+use crate::{
+    check_overscoped_allows, deny_rust_allow, guard_write, policy::Policy, DecisionBehavior,
+    DenyRustAllowOptions, HookOutput, PermissionDecision, ToolInput, WriteGuardOptions,
+};
+
+/// One hook's verdict on the input under inspection.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainFinding {
+    /// Which check produced this finding, e.g. `"policy:block-rm"`.
+    pub check: String,
+    pub decision: ExplainDecision,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplainDecision {
+    Deny,
+    Ask,
+    Allow,
+}
+
+fn finding_from_output(check: &str, output: &HookOutput) -> ExplainFinding {
+    let hso = &output.hook_specific_output;
+    if let Some(decision) = &hso.decision {
+        return ExplainFinding {
+            check: check.to_string(),
+            decision: match decision.behavior {
+                DecisionBehavior::Deny => ExplainDecision::Deny,
+                DecisionBehavior::Allow => ExplainDecision::Allow,
+            },
+            message: decision.message.clone(),
+        };
+    }
+
+    ExplainFinding {
+        check: check.to_string(),
+        decision: match hso.permission_decision {
+            Some(PermissionDecision::Deny) => ExplainDecision::Deny,
+            Some(PermissionDecision::Ask) => ExplainDecision::Ask,
+            _ => ExplainDecision::Allow,
+        },
+        message: hso
+            .permission_decision_reason
+            .clone()
+            .unwrap_or_default(),
+    }
+}
+
+/// Run a Bash command string through the policy engine and report every
+/// matching rule (not just the first), so users can see the full ruleset
+/// they're up against, not only what would have fired. An allow-list match
+/// is the exception: it is reported alone, and no later rule is checked.
+#[must_use]
+pub fn explain_command(cmd: &str, policy: &Policy) -> Vec<ExplainFinding> {
+    let mut findings = Vec::new();
+
+    for rule in &policy.allow {
+        if let Ok(re) = regex::Regex::new(&rule.pattern) {
+            if re.is_match(cmd) {
+                findings.push(ExplainFinding {
+                    check: format!("policy:{}", rule.name),
+                    decision: ExplainDecision::Allow,
+                    message: "matched an allow-list rule; later rules are skipped".to_string(),
+                });
+                return findings;
+            }
+        }
+    }
+
+    for rule in &policy.rules {
+        let Ok(re) = regex::Regex::new(&rule.pattern) else {
+            continue;
+        };
+        if !re.is_match(cmd) {
+            continue;
+        }
+        findings.push(ExplainFinding {
+            check: format!("policy:{}", rule.name),
+            decision: match rule.decision {
+                crate::policy::RuleDecision::Deny => ExplainDecision::Deny,
+                crate::policy::RuleDecision::Ask => ExplainDecision::Ask,
+                crate::policy::RuleDecision::Allow => ExplainDecision::Allow,
+            },
+            message: rule.message.clone(),
+        });
+    }
+
+    findings
+}
+
+/// Run a simulated Edit/Write of `file_path` with `content` through the
+/// file hooks (`deny-rust-allow`, `guard-write`, `overscoped-allow`) and
+/// report every one that matched.
+#[must_use]
+pub fn explain_file_edit(tool_name: &str, file_path: &str, content: &str) -> Vec<ExplainFinding> {
+    let mut findings = Vec::new();
+
+    let tool_input = ToolInput {
+        command: None,
+        old_string: None,
+        new_string: Some(content.to_string()),
+        content: Some(content.to_string()),
+        edits: None,
+        new_source: Some(content.to_string()),
+        file_path: Some(file_path.to_string()),
+    };
+
+    let deny_options = DenyRustAllowOptions {
+        expect: false,
+        additional_context: None,
+        permitted_lints: std::collections::HashSet::new(),
+        ignore_paths: Vec::new(),
+        require_reason: false,
+    };
+    if let Some(output) = deny_rust_allow(tool_name, &tool_input, &deny_options) {
+        findings.push(finding_from_output("deny-rust-allow", &output));
+    }
+
+    let cwd = std::env::current_dir().ok();
+    let write_options = cwd
+        .as_deref()
+        .map(|cwd| WriteGuardOptions::default().with_builtin_denies(cwd))
+        .unwrap_or_default();
+    if let Some(output) = guard_write(tool_name, &tool_input, cwd.as_deref(), &write_options) {
+        findings.push(finding_from_output("guard-write", &output));
+    }
+
+    if let Some(output) = check_overscoped_allows(file_path, content) {
+        findings.push(finding_from_output("overscoped-allow", &output));
+    }
+
+    findings
+}
+
+/// Render findings as the human-readable report printed by `explain`.
+#[must_use]
+pub fn render_report(findings: &[ExplainFinding]) -> String {
+    if findings.is_empty() {
+        return "No rules matched - this input would pass through unmodified.".to_string();
+    }
+
+    let mut report = String::new();
+    for finding in findings {
+        report.push_str(&format!(
+            "[{:?}] {}: {}\n",
+            finding.decision, finding.check, finding.message
+        ));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_command_reports_block_rm() {
+        let findings = explain_command("rm -rf /tmp", &Policy::defaults());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].decision, ExplainDecision::Deny);
+        assert_eq!(findings[0].check, "policy:block-rm");
+    }
+
+    #[test]
+    fn test_explain_command_safe_command_has_no_findings() {
+        assert!(explain_command("ls -la", &Policy::defaults()).is_empty());
+    }
+
+    #[test]
+    fn test_explain_file_edit_reports_allow_attribute() {
+        let findings =
+            explain_file_edit("Edit", "src/main.rs", "#[allow(dead_code)]\nfn foo() {}");
+        assert!(findings.iter().any(|f| f.check == "deny-rust-allow"));
+    }
+
+    #[test]
+    fn test_render_report_empty() {
+        assert_eq!(
+            render_report(&[]),
+            "No rules matched - this input would pass through unmodified."
+        );
+    }
+}
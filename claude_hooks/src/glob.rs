@@ -0,0 +1,64 @@
+//! Minimal glob matcher supporting `*` (any run of characters within a path
+//! segment) and `**` (any run of segments, including zero), enough for
+//! patterns like `tests/**`, `examples/**`, and `*.pb.rs`.
+
+/// Check whether `path` matches `pattern`.
+#[must_use]
+pub(crate) fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern_segments: Vec<&str> = pattern.split('/').collect();
+    let path_segments: Vec<&str> = path.split('/').collect();
+    match_segments(&pattern_segments, &path_segments)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (None, Some(_)) => false,
+        (Some(&"**"), _) => {
+            if pattern.len() == 1 {
+                return true;
+            }
+            (0..=path.len()).any(|i| match_segments(&pattern[1..], &path[i..]))
+        }
+        (Some(_), None) => false,
+        (Some(seg), Some(path_seg)) => {
+            segment_match(seg, path_seg) && match_segments(&pattern[1..], &path[1..])
+        }
+    }
+}
+
+fn segment_match(pattern: &str, text: &str) -> bool {
+    fn helper(pattern: &[u8], text: &[u8]) -> bool {
+        match (pattern.first(), text.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => (0..=text.len()).any(|i| helper(&pattern[1..], &text[i..])),
+            (Some(&p), Some(&t)) if p == t => helper(&pattern[1..], &text[1..]),
+            _ => false,
+        }
+    }
+    helper(pattern.as_bytes(), text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_star_matches_any_depth() {
+        assert!(glob_match("tests/**", "tests/foo/bar.rs"));
+        assert!(glob_match("tests/**", "tests/bar.rs"));
+        assert!(!glob_match("tests/**", "src/bar.rs"));
+    }
+
+    #[test]
+    fn test_star_matches_within_segment_only() {
+        assert!(glob_match("*.pb.rs", "generated.pb.rs"));
+        assert!(!glob_match("*.pb.rs", "nested/generated.pb.rs"));
+    }
+
+    #[test]
+    fn test_exact_match() {
+        assert!(glob_match("src/main.rs", "src/main.rs"));
+        assert!(!glob_match("src/main.rs", "src/lib.rs"));
+    }
+}
@@ -2,10 +2,22 @@ use regex::Regex;
 use seahorse::{App, Command, Context, Flag, FlagType};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::{HashMap, HashSet},
     io::{self, Read},
     sync::LazyLock,
 };
 
+mod explain;
+mod glob;
+mod overscoped_allow;
+mod policy;
+mod rust_lexer;
+mod write_guard;
+
+use overscoped_allow::check_overscoped_allows;
+use policy::Policy;
+use write_guard::{guard_write, WriteGuardOptions};
+
 // ============================================================================
 // Enums for type safety
 // ============================================================================
@@ -48,14 +60,29 @@ pub struct HookInput {
 #[derive(Debug, Deserialize)]
 pub struct ToolInput {
     pub command: Option<String>,
+    /// For Edit tool: the prior content being replaced, used to diff out the
+    /// lines this edit actually introduces
+    pub old_string: Option<String>,
     /// For Edit tool: the new content to replace
     pub new_string: Option<String>,
     /// For Write tool: the content to write
     pub content: Option<String>,
-    /// For Edit/Write tools: the file path
+    /// For MultiEdit tool: each individual old_string/new_string edit,
+    /// applied in sequence
+    pub edits: Option<Vec<MultiEditOp>>,
+    /// For NotebookEdit tool: the new cell source
+    pub new_source: Option<String>,
+    /// For Edit/Write/MultiEdit/NotebookEdit tools: the file path
     pub file_path: Option<String>,
 }
 
+/// One edit within a `MultiEdit` tool call.
+#[derive(Debug, Deserialize)]
+pub struct MultiEditOp {
+    pub old_string: Option<String>,
+    pub new_string: Option<String>,
+}
+
 // ============================================================================
 // Output structures
 // ============================================================================
@@ -111,40 +138,22 @@ fn output_hook_result(output: &HookOutput) {
 // ============================================================================
 
 #[cfg(not(windows))]
-static RM_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+pub(crate) static RM_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(^|[;&|()]\s*)(sudo\s+)?(command\s+)?(\\)?(\S*/)?rm(\s|$)").unwrap()
 });
 
 #[cfg(windows)]
-static RM_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+pub(crate) static RM_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
         r"(?i)(^|[;&|()]\s*)(sudo\s+)?(command\s+)?(\\)?(\S*[\\/])?(rm|del|rd|rmdir|remove-item)(\s|$)",
     )
     .unwrap()
 });
 
-/// Block rm command and suggest using trash instead
-fn block_rm(cmd: &str) -> Option<HookOutput> {
-    if RM_PATTERN.is_match(cmd) {
-        return Some(HookOutput {
-            hook_specific_output: HookSpecificOutput {
-                hook_event_name: HookEventName::PermissionRequest,
-                decision: Some(Decision {
-                    behavior: DecisionBehavior::Deny,
-                    message: "rm is forbidden. Use trash command to delete files. Example: trash <path...>".to_string(),
-                }),
-                permission_decision: None,
-                permission_decision_reason: None,
-            },
-        });
-    }
-
-    None
-}
-
-// Destructive patterns with descriptions
+// Destructive patterns with descriptions, used both directly and as the
+// default `policy::Policy` ruleset.
 #[cfg(not(windows))]
-const DESTRUCTIVE_PATTERNS: &[(&str, &str); 6] = &[
+pub(crate) const DESTRUCTIVE_PATTERNS: &[(&str, &str); 6] = &[
     // find ... -delete
     (r"find\s+.*-delete", "find with -delete option"),
     // find ... -exec rm/rmdir ...
@@ -172,150 +181,255 @@ const DESTRUCTIVE_PATTERNS: &[(&str, &str); 6] = &[
 ];
 
 #[cfg(windows)]
-const DESTRUCTIVE_PATTERNS: &[(&str, &str); 1] =
+pub(crate) const DESTRUCTIVE_PATTERNS: &[(&str, &str); 1] =
     &[(r"\|\s*(move|move-item)\b", "piped to move/move-item")];
 
-#[cfg(not(windows))]
-static FIND_CHECK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(^|[;&|()]\s*)find\s").unwrap());
+// ============================================================================
+// Rust #[allow(...)] / #[expect(...)] detection for PreToolUse (Edit/Write)
+// ============================================================================
 
-#[cfg(windows)]
-static FIND_CHECK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\|").unwrap());
+/// Pattern to detect #[allow(...)] or #![allow(...)] attributes in Rust code.
+/// Only used by `overscoped_allow`, which matches clippy's own span/line
+/// reporting against these; `deny_rust_allow` instead drives off
+/// `rust_lexer::find_attribute_matches`, which also unwraps `cfg_attr` and
+/// tolerates arbitrary whitespace.
+pub(crate) static RUST_ALLOW_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#!?\[allow\s*\(").unwrap());
 
-/// Confirm destructive find commands
-fn confirm_destructive_find(cmd: &str) -> Option<HookOutput> {
-    // First check if this is a find command
-    if !FIND_CHECK.is_match(cmd) {
-        return None;
-    }
+/// Options for `deny_rust_allow` hook
+pub struct DenyRustAllowOptions {
+    /// If true, suggest using #[expect(...)] instead of #[allow(...)]
+    /// If false, deny both #[allow(...)] and #[expect(...)]
+    pub expect: bool,
+    /// Additional context message to append to the denial reason
+    pub additional_context: Option<String>,
+    /// Lints that are always fine to `#[allow(...)]`/`#[expect(...)]`, e.g.
+    /// `non_upper_case_globals` around FFI bindings. Mirrors rustdoc's
+    /// internal "whitelisted lints" filtering: some lints are explicitly
+    /// ignored rather than all being treated identically. Empty means
+    /// "deny every lint", matching the original blanket behavior.
+    pub permitted_lints: HashSet<String>,
+    /// Glob patterns (e.g. `tests/**`, `*.pb.rs`) exempting matching file
+    /// paths from this check entirely.
+    pub ignore_paths: Vec<String>,
+    /// If true, an `#[allow(...)]` carrying a `reason = "..."` item is
+    /// permitted even when it would otherwise be denied; a bare
+    /// `#[allow(...)]` with no reason is still denied. Mirrors rustc's own
+    /// `reason` field on lint attributes, recast as a mandatory
+    /// justification instead of an optional one.
+    pub require_reason: bool,
+}
 
-    for (pattern, description) in DESTRUCTIVE_PATTERNS {
-        let re = Regex::new(&format!("(?i){pattern}")).unwrap();
-        if re.is_match(cmd) {
-            return Some(HookOutput {
-                hook_specific_output: HookSpecificOutput {
-                    hook_event_name: HookEventName::PermissionRequest,
-                    decision: None,
-                    permission_decision: Some(PermissionDecision::Ask),
-                    permission_decision_reason: Some(format!(
-                        "Destructive find command detected: {description}. \
-                         This operation may delete or modify files. Please confirm."
-                    )),
-                },
-            });
-        }
-    }
+/// Is `file_path` covered by one of `ignore_paths`? Patterns are matched
+/// against the full path and, for patterns with no `/`, against the file
+/// name alone (so `*.pb.rs` matches regardless of directory).
+fn path_is_ignored(file_path: &str, ignore_paths: &[String]) -> bool {
+    ignore_paths.iter().any(|pattern| {
+        glob::glob_match(pattern, file_path)
+            || (!pattern.contains('/')
+                && std::path::Path::new(file_path)
+                    .file_name()
+                    .and_then(|f| f.to_str())
+                    .is_some_and(|name| glob::glob_match(pattern, name)))
+    })
+}
 
-    None
+/// A single-line opt-out marker (`// allow-attr-ok: <reason>`) on the line
+/// immediately preceding a match suppresses the deny for that one attribute,
+/// giving users an auditable carve-out instead of disabling the hook wholesale.
+const ESCAPE_HATCH_MARKER: &str = "// allow-attr-ok:";
+
+fn previous_line(content: &str, pos: usize) -> Option<&str> {
+    let line_start = content[..pos].rfind('\n').map_or(0, |p| p + 1);
+    if line_start == 0 {
+        return None;
+    }
+    let prev_end = line_start - 1;
+    let prev_start = content[..prev_end].rfind('\n').map_or(0, |p| p + 1);
+    Some(&content[prev_start..prev_end])
 }
 
-// ============================================================================
-// Rust #[allow(...)] / #[expect(...)] detection for PreToolUse (Edit/Write)
-// ============================================================================
+fn has_escape_hatch(content: &str, attr_start: usize) -> bool {
+    previous_line(content, attr_start)
+        .map(str::trim)
+        .is_some_and(|line| line.starts_with(ESCAPE_HATCH_MARKER))
+}
 
-/// Pattern to detect #[allow(...)] or #![allow(...)] attributes in Rust code
-static RUST_ALLOW_PATTERN: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"#!?\[allow\s*\(").unwrap());
+/// One `#[name(...)]` occurrence's lint identifiers, separated out from any
+/// `reason = "..."` item in the same argument list.
+struct AttributeLints {
+    lints: Vec<String>,
+    has_reason: bool,
+}
 
-/// Pattern to detect #[expect(...)] or #![expect(...)] attributes in Rust code
-static RUST_EXPECT_PATTERN: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"#!?\[expect\s*\(").unwrap());
-
-/// Check if a position in the content is inside a line comment or string literal
-fn is_in_comment_or_string(content: &str, match_start: usize) -> bool {
-    let before = &content[..match_start];
-
-    // Check if in line comment (// ...)
-    // Find the last newline before the match
-    let line_start = before.rfind('\n').map_or(0, |p| p + 1);
-    let current_line = &before[line_start..];
-    if current_line.contains("//") {
-        return true;
-    }
-
-    // Check if inside a block comment (/* ... */)
-    // Count /* and */ before the position
-    let block_open = before.matches("/*").count();
-    let block_close = before.matches("*/").count();
-    if block_open > block_close {
-        return true;
-    }
-
-    // Check if inside a string literal
-    // This is a simplified check - count unescaped quotes
-    // For raw strings r#"..."#, we do a simple heuristic
-
-    // Check for raw string r#"..."# - look for unclosed r#" or r"
-    // Find the last r#" or r" that isn't closed
-    let mut in_raw_string = false;
-    let mut i = 0;
-    let bytes = before.as_bytes();
-    while i < bytes.len() {
-        if in_raw_string {
-            // Inside raw string - look for closing "# pattern
-            if bytes[i] == b'"' {
-                // This could be the end - raw strings end with "# (matching # count)
-                // Simplified: just assume any " might end it
-                in_raw_string = false;
-            }
-        } else {
-            // Check for raw string start: r" or r#" or r##" etc.
-            if bytes[i] == b'r' && i + 1 < bytes.len() {
-                let mut j = i + 1;
-                // Count # signs
-                while j < bytes.len() && bytes[j] == b'#' {
-                    j += 1;
-                }
-                if j < bytes.len() && bytes[j] == b'"' {
-                    in_raw_string = true;
-                    i = j + 1;
+/// Parse every occurrence of attribute `name` in `content` - as a bare
+/// `#[name(...)]`/`#![name(...)]`, or nested inside
+/// `#[cfg_attr(condition, name(...))]` - into its lint identifiers (preserving
+/// tool prefixes like `clippy::` and `rustc::`) and whether it carries a
+/// `reason = "..."` justification, as rustc's lint attributes accept.
+/// Matches inside comments and string/char literals are never counted, and
+/// an `// allow-attr-ok:` escape hatch on the preceding line suppresses that
+/// one occurrence entirely.
+fn parse_attribute_occurrences(content: &str, name: &str) -> Vec<AttributeLints> {
+    rust_lexer::find_attribute_matches(content, name)
+        .into_iter()
+        .filter(|attr| !has_escape_hatch(content, attr.start))
+        .map(|attr| {
+            let mut lints = Vec::new();
+            let mut has_reason = false;
+
+            for item in rust_lexer::split_top_level_commas(&attr.args) {
+                let item = item.trim();
+                if item.is_empty() {
                     continue;
                 }
-            }
-            // Check for regular string
-            if bytes[i] == b'"' && (i == 0 || bytes[i - 1] != b'\\') {
-                // Toggle string state - but we need to find the closing quote
-                let mut k = i + 1;
-                while k < bytes.len() {
-                    if bytes[k] == b'"' && bytes[k - 1] != b'\\' {
-                        break;
+                if let Some(rest) = item.strip_prefix("reason") {
+                    if rest.trim_start().starts_with('=') {
+                        has_reason = true;
+                        continue;
                     }
-                    k += 1;
-                }
-                if k >= bytes.len() {
-                    // Unclosed string
-                    return true;
                 }
-                i = k + 1;
-                continue;
+                lints.push(item.to_string());
             }
-        }
-        i += 1;
+
+            AttributeLints { lints, has_reason }
+        })
+        .collect()
+}
+
+/// Lints denied across every occurrence of attribute `name`: unpermitted
+/// lints that aren't in `permitted_lints`, skipping occurrences entirely
+/// when `enforce_reason` is set and the occurrence already carries a
+/// `reason = "..."` justification.
+fn denied_lints_for(
+    content: &str,
+    name: &str,
+    permitted_lints: &HashSet<String>,
+    enforce_reason: bool,
+) -> Vec<String> {
+    parse_attribute_occurrences(content, name)
+        .into_iter()
+        .filter(|occ| !(enforce_reason && occ.has_reason))
+        .flat_map(|occ| unpermitted_lints(&occ.lints, permitted_lints))
+        .collect()
+}
+
+/// Lints not in `permitted_lints`.
+fn unpermitted_lints(lints: &[String], permitted_lints: &HashSet<String>) -> Vec<String> {
+    lints
+        .iter()
+        .filter(|lint| !permitted_lints.contains(lint.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Guidance appended to the denial message for a denied `#[allow(...)]`:
+/// when `require_reason` is set, point at adding a `reason = "..."`
+/// justification instead of a blanket "fix the underlying issue".
+fn allow_guidance(denied_allow: &[String], require_reason: bool) -> String {
+    if require_reason {
+        format!(
+            "Adding #[allow({})] without a `reason = \"...\"` justification is not permitted. \
+             Add one, e.g. #[allow({}, reason = \"...\")], or fix the underlying issue instead of \
+             suppressing the warning.",
+            denied_allow.join(", "),
+            denied_allow.first().map_or("lint", String::as_str)
+        )
+    } else {
+        format!(
+            "Adding #[allow({})] or #![allow(...)] attributes is not permitted. \
+             Fix the underlying issue instead of suppressing the warning.",
+            denied_allow.join(", ")
+        )
     }
+}
 
-    if in_raw_string {
-        return true;
+/// Lines present in `new_text` more times than in `old_text`, rejoined with
+/// `\n` - a line-level approximation of "what this edit added". This is a
+/// multiset diff, not set membership: each line in `old_text` can only
+/// cancel out one matching occurrence in `new_text`, so if the hunk already
+/// had one `#[allow(dead_code)]` and the edit introduces a second, that
+/// second occurrence still counts as added instead of being hidden by the
+/// first. It doesn't track reordering or line-level moves, but that's
+/// enough to tell freshly introduced lines apart from ones that were
+/// already sitting in the file and simply carried through into `new_text`
+/// unchanged.
+fn added_lines(old_text: &str, new_text: &str) -> String {
+    let mut old_line_counts: HashMap<&str, usize> = HashMap::new();
+    for line in old_text.lines() {
+        *old_line_counts.entry(line).or_insert(0) += 1;
     }
 
-    false
+    new_text
+        .lines()
+        .filter(|line| match old_line_counts.get_mut(line) {
+            Some(remaining) if *remaining > 0 => {
+                *remaining -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-/// Find all matches of a pattern that are not in comments or strings
-fn find_real_matches(content: &str, pattern: &Regex) -> bool {
-    for m in pattern.find_iter(content) {
-        if !is_in_comment_or_string(content, m.start()) {
-            return true;
+/// Normalize a file-mutating tool call's payload into the text fragment(s)
+/// it actually introduces, so `deny_rust_allow` flags only attributes this
+/// call adds rather than ones already present in the file:
+/// - `Edit`: the lines of `new_string` not already in `old_string`.
+/// - `MultiEdit`: one such fragment per edit in `edits`.
+/// - `Write`: the whole `content`, since there's no prior content to diff
+///   against in the payload itself.
+/// - `NotebookEdit`: the whole `new_source` of the edited cell.
+fn added_text_fragments(tool_name: &str, tool_input: &ToolInput) -> Vec<String> {
+    let diffed = |old: Option<&str>, new: &str| match old {
+        Some(old) => added_lines(old, new),
+        None => new.to_string(),
+    };
+
+    match tool_name {
+        "Edit" => {
+            let fragment = diffed(
+                tool_input.old_string.as_deref(),
+                tool_input.new_string.as_deref().unwrap_or_default(),
+            );
+            if fragment.is_empty() {
+                Vec::new()
+            } else {
+                vec![fragment]
+            }
         }
+        "MultiEdit" => tool_input
+            .edits
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|edit| {
+                let fragment = diffed(
+                    edit.old_string.as_deref(),
+                    edit.new_string.as_deref().unwrap_or_default(),
+                );
+                (!fragment.is_empty()).then_some(fragment)
+            })
+            .collect(),
+        "Write" => {
+            let content = tool_input.content.as_deref().unwrap_or_default();
+            if content.is_empty() {
+                Vec::new()
+            } else {
+                vec![content.to_string()]
+            }
+        }
+        "NotebookEdit" => {
+            let new_source = tool_input.new_source.as_deref().unwrap_or_default();
+            if new_source.is_empty() {
+                Vec::new()
+            } else {
+                vec![new_source.to_string()]
+            }
+        }
+        _ => Vec::new(),
     }
-    false
-}
-
-/// Options for `deny_rust_allow` hook
-pub struct DenyRustAllowOptions {
-    /// If true, suggest using #[expect(...)] instead of #[allow(...)]
-    /// If false, deny both #[allow(...)] and #[expect(...)]
-    pub expect: bool,
-    /// Additional context message to append to the denial reason
-    pub additional_context: Option<String>,
 }
 
 /// Deny adding #[allow(...)] or #![allow(...)] attributes to Rust files
@@ -325,8 +439,7 @@ fn deny_rust_allow(
     tool_input: &ToolInput,
     options: &DenyRustAllowOptions,
 ) -> Option<HookOutput> {
-    // Only check Edit and Write tools
-    if tool_name != "Edit" && tool_name != "Write" {
+    if !matches!(tool_name, "Edit" | "MultiEdit" | "Write" | "NotebookEdit") {
         return None;
     }
 
@@ -339,28 +452,43 @@ fn deny_rust_allow(
         return None;
     }
 
-    // Get the content being written/edited
-    let content = tool_input
-        .new_string
-        .as_deref()
-        .or(tool_input.content.as_deref())
-        .unwrap_or_default();
+    if path_is_ignored(file_path, &options.ignore_paths) {
+        return None;
+    }
 
-    if content.is_empty() {
+    let fragments = added_text_fragments(tool_name, tool_input);
+    if fragments.is_empty() {
         return None;
     }
 
-    // Use find_real_matches to ignore comments and string literals
-    let has_allow = find_real_matches(content, &RUST_ALLOW_PATTERN);
-    let has_expect = find_real_matches(content, &RUST_EXPECT_PATTERN);
+    let mut denied_allow = Vec::new();
+    let mut denied_expect = Vec::new();
+    for fragment in &fragments {
+        denied_allow.extend(denied_lints_for(
+            fragment,
+            "allow",
+            &options.permitted_lints,
+            options.require_reason,
+        ));
+        denied_expect.extend(denied_lints_for(
+            fragment,
+            "expect",
+            &options.permitted_lints,
+            false,
+        ));
+    }
+    let has_allow = !denied_allow.is_empty();
+    let has_expect = !denied_expect.is_empty();
 
     // Build the denial message based on options
     let denial_reason = if options.expect {
         // --expect=true: only deny #[allow], suggest using #[expect] instead
         if has_allow {
-            let mut msg = "Adding #[allow(...)] or #![allow(...)] attributes is not permitted. \
-                           Use #[expect(...)] instead, which will warn when the lint is no longer triggered."
-                .to_string();
+            let mut msg = format!(
+                "Adding #[allow({})] or #![allow(...)] attributes is not permitted. \
+                 Use #[expect(...)] instead, which will warn when the lint is no longer triggered.",
+                denied_allow.join(", ")
+            );
             if let Some(ref ctx) = options.additional_context {
                 msg.push(' ');
                 msg.push_str(ctx);
@@ -373,17 +501,19 @@ fn deny_rust_allow(
         // no --expect: deny both #[allow] and #[expect]
         if has_allow || has_expect {
             let mut msg = if has_allow && has_expect {
-                "Adding #[allow(...)] or #[expect(...)] attributes is not permitted. \
-                 Fix the underlying issue instead of suppressing the warning."
-                    .to_string()
+                format!(
+                    "{} Also, adding #[expect({})] or #![expect(...)] attributes is not permitted.",
+                    allow_guidance(&denied_allow, options.require_reason),
+                    denied_expect.join(", ")
+                )
             } else if has_allow {
-                "Adding #[allow(...)] or #![allow(...)] attributes is not permitted. \
-                 Fix the underlying issue instead of suppressing the warning."
-                    .to_string()
+                allow_guidance(&denied_allow, options.require_reason)
             } else {
-                "Adding #[expect(...)] or #![expect(...)] attributes is not permitted. \
-                 Fix the underlying issue instead of suppressing the warning."
-                    .to_string()
+                format!(
+                    "Adding #[expect({})] or #![expect(...)] attributes is not permitted. \
+                     Fix the underlying issue instead of suppressing the warning.",
+                    denied_expect.join(", ")
+                )
             };
             if let Some(ref ctx) = options.additional_context {
                 msg.push(' ');
@@ -409,7 +539,28 @@ fn deny_rust_allow(
 // Command handlers
 // ============================================================================
 
-fn permission_request_action(_c: &Context) {
+/// Load the effective policy for `permission-request`: the `--config` flag
+/// if given, else `~/.config/claude_hooks/policy.toml` if it exists, merged
+/// in front of the built-in defaults. Falls back to pure defaults if no
+/// config file is found or it fails to parse.
+fn load_policy(c: &Context) -> Policy {
+    let config_path = c
+        .string_flag("config")
+        .ok()
+        .map(std::path::PathBuf::from)
+        .or_else(Policy::default_path);
+
+    let Some(path) = config_path else {
+        return Policy::defaults();
+    };
+
+    match Policy::load(&path) {
+        Ok(policy) => policy.merged_with_defaults(),
+        Err(_) => Policy::defaults(),
+    }
+}
+
+fn permission_request_action(c: &Context) {
     let Ok(data) = read_hook_input() else {
         return;
     };
@@ -429,7 +580,8 @@ fn permission_request_action(_c: &Context) {
         return;
     }
 
-    if let Some(output) = block_rm(cmd).or_else(|| confirm_destructive_find(cmd)) {
+    let policy = load_policy(c);
+    if let Some(output) = policy.evaluate(cmd) {
         output_hook_result(&output);
     }
 }
@@ -440,7 +592,7 @@ fn deny_rust_allow_action(c: &Context) {
     };
 
     let tool_name = data.tool_name.as_deref().unwrap_or_default();
-    if tool_name != "Edit" && tool_name != "Write" {
+    if !matches!(tool_name, "Edit" | "MultiEdit" | "Write" | "NotebookEdit") {
         return;
     }
 
@@ -451,10 +603,24 @@ fn deny_rust_allow_action(c: &Context) {
     // Parse flags
     let expect = c.bool_flag("expect");
     let additional_context = c.string_flag("additional-context").ok();
+    let permitted_lints = c
+        .string_flag("permitted-lints")
+        .ok()
+        .map(|csv| csv.split(',').map(|s| s.trim().to_string()).collect())
+        .unwrap_or_default();
+    let ignore_paths = c
+        .string_flag("ignore-paths")
+        .ok()
+        .map(|csv| csv.split(',').map(|s| s.trim().to_string()).collect())
+        .unwrap_or_default();
+    let require_reason = c.bool_flag("require-reason");
 
     let options = DenyRustAllowOptions {
         expect,
         additional_context,
+        permitted_lints,
+        ignore_paths,
+        require_reason,
     };
 
     if let Some(output) = deny_rust_allow(tool_name, tool_input, &options) {
@@ -462,6 +628,106 @@ fn deny_rust_allow_action(c: &Context) {
     }
 }
 
+fn guard_write_action(c: &Context) {
+    let Ok(data) = read_hook_input() else {
+        return;
+    };
+
+    let tool_name = data.tool_name.as_deref().unwrap_or_default();
+    if tool_name != "Edit" && tool_name != "Write" {
+        return;
+    }
+
+    let Some(tool_input) = data.tool_input.as_ref() else {
+        return;
+    };
+
+    let cwd = std::env::current_dir().ok();
+
+    let mut options = WriteGuardOptions::default();
+    if let Ok(allow) = c.string_flag("allow-write") {
+        options.allow = allow.split(',').map(std::path::PathBuf::from).collect();
+    }
+    if let Ok(deny) = c.string_flag("deny-write") {
+        options.deny = deny.split(',').map(std::path::PathBuf::from).collect();
+    }
+    if let Some(ref cwd) = cwd {
+        options = options.with_builtin_denies(cwd);
+    }
+
+    if let Some(output) = guard_write(tool_name, tool_input, cwd.as_deref(), &options) {
+        output_hook_result(&output);
+    }
+}
+
+/// `overscoped-allow`: an explicit, user-invoked audit - *not* a `PreToolUse`
+/// hook. Checking a lint's scope means running `cargo clippy` on the whole
+/// crate, which takes far too long to sit in front of every `Edit`/`Write`
+/// (Claude Code's hook timeout would trip long before clippy finishes on any
+/// real-sized crate). So this reads file paths given directly on the command
+/// line - the same offline, run-it-yourself shape as `explain` - rather than
+/// a JSON payload on stdin, and is meant to be run on demand (or in CI), not
+/// wired into a hooks config.
+fn overscoped_allow_action(c: &Context) {
+    if c.args.is_empty() {
+        eprintln!("Usage: claude_hooks overscoped-allow <path.rs> [<path.rs>...]");
+        return;
+    }
+
+    for file_path in &c.args {
+        if !std::path::Path::new(file_path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("rs"))
+        {
+            continue;
+        }
+
+        let Ok(content) = std::fs::read_to_string(file_path) else {
+            eprintln!("warning: could not read {file_path}");
+            continue;
+        };
+
+        if let Some(output) = check_overscoped_allows(file_path, &content) {
+            if let Some(reason) = output.hook_specific_output.permission_decision_reason {
+                println!("{reason}");
+            }
+        }
+    }
+}
+
+/// `explain`: run a command (positional arg) or a simulated file edit
+/// (`--file-path`/`--content`) through every registered hook and print what
+/// matched, without needing a Claude Code session or stdin JSON.
+fn explain_action(c: &Context) {
+    let json = c.bool_flag("json");
+    let file_path = c.string_flag("file-path").ok();
+    let content = c.string_flag("content").ok();
+
+    let findings = if let (Some(file_path), Some(content)) = (&file_path, &content) {
+        let tool_name = c
+            .string_flag("tool-name")
+            .unwrap_or_else(|_| "Edit".to_string());
+        explain::explain_file_edit(&tool_name, file_path, content)
+    } else {
+        let Some(cmd) = c.args.first() else {
+            eprintln!(
+                "Usage: claude_hooks explain <command> | --file-path <path> --content <text>"
+            );
+            return;
+        };
+        let policy = load_policy(c);
+        explain::explain_command(cmd, &policy)
+    };
+
+    if json {
+        if let Ok(json) = serde_json::to_string_pretty(&findings) {
+            println!("{json}");
+        }
+    } else {
+        print!("{}", explain::render_report(&findings));
+    }
+}
+
 // ============================================================================
 // Main
 // ============================================================================
@@ -475,11 +741,17 @@ fn main() {
         .command(
             Command::new("permission-request")
                 .description("Check and handle permission requests for Bash commands")
+                .flag(
+                    Flag::new("config", FlagType::String)
+                        .description(
+                            "Path to a policy.toml file of allow/deny/ask rules (default: ~/.config/claude_hooks/policy.toml)",
+                        ),
+                )
                 .action(permission_request_action),
         )
         .command(
             Command::new("deny-rust-allow")
-                .description("Deny #[allow(...)] attributes in Rust files (Edit/Write)")
+                .description("Deny #[allow(...)] attributes newly added to Rust files (Edit/MultiEdit/Write/NotebookEdit)")
                 .flag(
                     Flag::new("expect", FlagType::Bool)
                         .description("If true, suggest #[expect(...)] instead of denying. If false (default), deny both #[allow] and #[expect]"),
@@ -488,7 +760,64 @@ fn main() {
                     Flag::new("additional-context", FlagType::String)
                         .description("Additional context message to append to the denial reason"),
                 )
+                .flag(
+                    Flag::new("permitted-lints", FlagType::String)
+                        .description("Comma-separated lint names that are always permitted to be allowed/expected, e.g. non_upper_case_globals"),
+                )
+                .flag(
+                    Flag::new("ignore-paths", FlagType::String)
+                        .description("Comma-separated glob patterns (e.g. tests/**,*.pb.rs) exempting matching paths from this check"),
+                )
+                .flag(
+                    Flag::new("require-reason", FlagType::Bool)
+                        .description("If true, permit #[allow(...)] when it carries a reason = \"...\" justification; deny bare #[allow(...)]"),
+                )
                 .action(deny_rust_allow_action),
+        )
+        .command(
+            Command::new("guard-write")
+                .description("Deny Edit/Write calls whose target path falls outside allow-write roots or inside deny-write roots")
+                .flag(
+                    Flag::new("allow-write", FlagType::String)
+                        .description("Comma-separated list of root paths writes are permitted under"),
+                )
+                .flag(
+                    Flag::new("deny-write", FlagType::String)
+                        .description("Comma-separated list of root paths writes are always denied under"),
+                )
+                .action(guard_write_action),
+        )
+        .command(
+            Command::new("overscoped-allow")
+                .usage("claude_hooks overscoped-allow <path.rs> [<path.rs>...]")
+                .description("Audit given Rust files for #[allow(...)] attributes whose lint is never reported by cargo clippy in their scope. Run this by hand or in CI - it shells out to cargo clippy on the whole crate, so it is not suitable as a PreToolUse hook")
+                .action(overscoped_allow_action),
+        )
+        .command(
+            Command::new("explain")
+                .usage("claude_hooks explain <command> [--json]")
+                .description("Run a command or file edit through every hook and report which rules matched, without needing stdin JSON")
+                .flag(
+                    Flag::new("json", FlagType::Bool)
+                        .description("Print findings as a JSON array instead of a human-readable report"),
+                )
+                .flag(
+                    Flag::new("config", FlagType::String)
+                        .description("Path to a policy.toml file (same as permission-request --config)"),
+                )
+                .flag(
+                    Flag::new("file-path", FlagType::String)
+                        .description("Simulate an Edit/Write of this file path instead of a Bash command"),
+                )
+                .flag(
+                    Flag::new("content", FlagType::String)
+                        .description("Content to check when --file-path is given"),
+                )
+                .flag(
+                    Flag::new("tool-name", FlagType::String)
+                        .description("Tool name to simulate with --file-path (default: Edit)"),
+                )
+                .action(explain_action),
         );
 
     app.run(args);
@@ -503,104 +832,27 @@ mod tests {
     use super::*;
 
     // -------------------------------------------------------------------------
-    // Helper functions tests
+    // Comment/string-literal detection and `find_real_matches` now live in
+    // rust_lexer.rs, which has its own test module.
     // -------------------------------------------------------------------------
 
-    #[test]
-    fn test_is_in_comment_or_string_line_comment() {
-        let content = "// #[allow(dead_code)]";
-        assert!(is_in_comment_or_string(content, 3));
-    }
-
-    #[test]
-    fn test_is_in_comment_or_string_not_in_comment() {
-        let content = "#[allow(dead_code)]";
-        assert!(!is_in_comment_or_string(content, 0));
-    }
-
-    #[test]
-    fn test_is_in_comment_or_string_block_comment() {
-        let content = "/* #[allow(dead_code)] */";
-        assert!(is_in_comment_or_string(content, 3));
-    }
-
-    #[test]
-    fn test_is_in_comment_or_string_string_literal() {
-        // Content: let s = "#[allow(dead_code)]";
-        let content = "let s = \"#[allow(dead_code)]\";";
-        assert!(is_in_comment_or_string(content, 9));
-    }
-
-    #[test]
-    fn test_is_in_comment_or_string_after_comment() {
-        let content = "// comment\n#[allow(dead_code)]";
-        assert!(!is_in_comment_or_string(content, 11));
-    }
-
-    #[test]
-    fn test_find_real_matches_ignores_comments() {
-        let content = "// #[allow(dead_code)]\nfn foo() {}";
-        assert!(!find_real_matches(content, &RUST_ALLOW_PATTERN));
-    }
-
-    #[test]
-    fn test_find_real_matches_detects_real_allow() {
-        let content = "#[allow(dead_code)]\nfn foo() {}";
-        assert!(find_real_matches(content, &RUST_ALLOW_PATTERN));
-    }
-
-    #[test]
-    fn test_find_real_matches_after_comment() {
-        let content = "// comment\n#[allow(dead_code)]";
-        assert!(find_real_matches(content, &RUST_ALLOW_PATTERN));
-    }
-
     // -------------------------------------------------------------------------
-    // block_rm tests
+    // permission-request: default policy (formerly block_rm/confirm_destructive_find)
+    //
+    // See policy.rs for the rest of the rule-evaluation test coverage.
     // -------------------------------------------------------------------------
 
     #[test]
-    fn test_block_rm_simple() {
-        assert!(block_rm("rm file.txt").is_some());
-    }
-
-    #[test]
-    fn test_block_rm_with_flags() {
-        assert!(block_rm("rm -rf /tmp/test").is_some());
-    }
-
-    #[test]
-    fn test_block_rm_with_sudo() {
-        assert!(block_rm("sudo rm -rf /").is_some());
-    }
-
-    #[test]
-    fn test_block_rm_in_pipeline() {
-        assert!(block_rm("echo test && rm file.txt").is_some());
-    }
-
-    #[test]
-    fn test_block_rm_allows_other_commands() {
-        assert!(block_rm("ls -la").is_none());
-        assert!(block_rm("trash file.txt").is_none());
+    fn test_default_policy_blocks_rm() {
+        let policy = Policy::defaults();
+        assert!(policy.evaluate("rm file.txt").is_some());
+        assert!(policy.evaluate("sudo rm -rf /").is_some());
     }
 
     #[test]
-    fn test_block_rm_allows_grep_rm() {
-        // "rm" as part of another word should not match
-        assert!(block_rm("grep -r 'pattern' .").is_none());
-        assert!(block_rm("rma -rm").is_none());
-    }
-
-    // -------------------------------------------------------------------------
-    // confirm_destructive_find tests
-    // -------------------------------------------------------------------------
-
-    #[test]
-    fn test_confirm_destructive_find_delete() {
-        let result = confirm_destructive_find("find . -name '*.tmp' -delete");
-        assert!(result.is_some());
-        let output = result.unwrap();
+    fn test_default_policy_asks_on_destructive_find() {
+        let policy = Policy::defaults();
+        let output = policy.evaluate("find . -name '*.tmp' -delete").unwrap();
         assert!(matches!(
             output.hook_specific_output.permission_decision,
             Some(PermissionDecision::Ask)
@@ -608,21 +860,10 @@ mod tests {
     }
 
     #[test]
-    fn test_confirm_destructive_find_exec_rm() {
-        let result = confirm_destructive_find("find . -exec rm {} \\;");
-        assert!(result.is_some());
-    }
-
-    #[test]
-    fn test_confirm_destructive_find_xargs_rm() {
-        let result = confirm_destructive_find("find . -name '*.tmp' | xargs rm");
-        assert!(result.is_some());
-    }
-
-    #[test]
-    fn test_confirm_destructive_find_safe() {
-        assert!(confirm_destructive_find("find . -name '*.rs'").is_none());
-        assert!(confirm_destructive_find("find . -type f -print").is_none());
+    fn test_default_policy_allows_safe_commands() {
+        let policy = Policy::defaults();
+        assert!(policy.evaluate("ls -la").is_none());
+        assert!(policy.evaluate("find . -name '*.rs'").is_none());
     }
 
     // -------------------------------------------------------------------------
@@ -632,8 +873,11 @@ mod tests {
     fn make_tool_input(file_path: &str, new_string: &str) -> ToolInput {
         ToolInput {
             command: None,
+            old_string: None,
             new_string: Some(new_string.to_string()),
             content: None,
+            edits: None,
+            new_source: None,
             file_path: Some(file_path.to_string()),
         }
     }
@@ -642,6 +886,9 @@ mod tests {
         DenyRustAllowOptions {
             expect: false,
             additional_context: None,
+            permitted_lints: HashSet::new(),
+            ignore_paths: Vec::new(),
+            require_reason: false,
         }
     }
 
@@ -677,6 +924,9 @@ mod tests {
         let options = DenyRustAllowOptions {
             expect: true,
             additional_context: None,
+            permitted_lints: HashSet::new(),
+            ignore_paths: Vec::new(),
+            require_reason: false,
         };
         let result = deny_rust_allow("Edit", &input, &options);
         assert!(result.is_none()); // Should allow #[expect]
@@ -688,6 +938,9 @@ mod tests {
         let options = DenyRustAllowOptions {
             expect: true,
             additional_context: None,
+            permitted_lints: HashSet::new(),
+            ignore_paths: Vec::new(),
+            require_reason: false,
         };
         let result = deny_rust_allow("Edit", &input, &options);
         assert!(result.is_some()); // Should still deny #[allow]
@@ -728,6 +981,9 @@ mod tests {
         let options = DenyRustAllowOptions {
             expect: false,
             additional_context: Some("See guidelines".to_string()),
+            permitted_lints: HashSet::new(),
+            ignore_paths: Vec::new(),
+            require_reason: false,
         };
         let result = deny_rust_allow("Edit", &input, &options);
         assert!(result.is_some());
@@ -739,6 +995,86 @@ mod tests {
         assert!(reason.contains("See guidelines"));
     }
 
+    #[test]
+    fn test_deny_rust_allow_permits_whitelisted_lint() {
+        let input = make_tool_input("src/main.rs", "#[allow(non_upper_case_globals)]");
+        let options = DenyRustAllowOptions {
+            expect: false,
+            additional_context: None,
+            permitted_lints: HashSet::from(["non_upper_case_globals".to_string()]),
+            ignore_paths: Vec::new(),
+            require_reason: false,
+        };
+        let result = deny_rust_allow("Edit", &input, &options);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_mixed_lints_only_denies_unpermitted_ones() {
+        let input = make_tool_input(
+            "src/main.rs",
+            "#[allow(non_upper_case_globals, dead_code)]",
+        );
+        let options = DenyRustAllowOptions {
+            expect: false,
+            additional_context: None,
+            permitted_lints: HashSet::from(["non_upper_case_globals".to_string()]),
+            ignore_paths: Vec::new(),
+            require_reason: false,
+        };
+        let result = deny_rust_allow("Edit", &input, &options).unwrap();
+        let reason = result
+            .hook_specific_output
+            .permission_decision_reason
+            .unwrap();
+        assert!(reason.contains("dead_code"));
+        assert!(!reason.contains("non_upper_case_globals"));
+    }
+
+    #[test]
+    fn test_deny_rust_allow_ignores_exempted_path() {
+        let input = make_tool_input("tests/fixtures/gen.rs", "#[allow(dead_code)]");
+        let options = DenyRustAllowOptions {
+            ignore_paths: vec!["tests/**".to_string()],
+            ..default_options()
+        };
+        assert!(deny_rust_allow("Edit", &input, &options).is_none());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_ignore_paths_does_not_affect_unmatched_files() {
+        let input = make_tool_input("src/main.rs", "#[allow(dead_code)]");
+        let options = DenyRustAllowOptions {
+            ignore_paths: vec!["tests/**".to_string()],
+            ..default_options()
+        };
+        assert!(deny_rust_allow("Edit", &input, &options).is_some());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_escape_hatch_comment_suppresses_one_attribute() {
+        let input = make_tool_input(
+            "src/main.rs",
+            "// allow-attr-ok: FFI binding name must stay as-is\n#[allow(non_upper_case_globals)]\nstatic mut x: i32 = 0;",
+        );
+        assert!(deny_rust_allow("Edit", &input, &default_options()).is_none());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_escape_hatch_only_covers_preceding_attribute() {
+        let input = make_tool_input(
+            "src/main.rs",
+            "// allow-attr-ok: fine\n#[allow(dead_code)]\nfn a() {}\n#[allow(unused)]\nfn b() {}",
+        );
+        let result = deny_rust_allow("Edit", &input, &default_options()).unwrap();
+        let reason = result
+            .hook_specific_output
+            .permission_decision_reason
+            .unwrap();
+        assert!(reason.contains("unused"));
+        assert!(!reason.contains("dead_code"));
+    }
+
     #[test]
     fn test_deny_rust_allow_case_insensitive_extension() {
         let input = make_tool_input("src/main.RS", "#[allow(dead_code)]");
@@ -752,4 +1088,158 @@ mod tests {
         let result = deny_rust_allow("Edit", &input, &default_options());
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_deny_rust_allow_require_reason_denies_bare_allow() {
+        let input = make_tool_input("src/main.rs", "#[allow(dead_code)]");
+        let options = DenyRustAllowOptions {
+            require_reason: true,
+            ..default_options()
+        };
+        let result = deny_rust_allow("Edit", &input, &options);
+        assert!(result.is_some());
+        let reason = result
+            .unwrap()
+            .hook_specific_output
+            .permission_decision_reason
+            .unwrap();
+        assert!(reason.contains("reason"));
+    }
+
+    #[test]
+    fn test_deny_rust_allow_require_reason_permits_justified_allow() {
+        let input = make_tool_input(
+            "src/main.rs",
+            "#[allow(dead_code, reason = \"FFI stub, removed in #123\")]",
+        );
+        let options = DenyRustAllowOptions {
+            require_reason: true,
+            ..default_options()
+        };
+        assert!(deny_rust_allow("Edit", &input, &options).is_none());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_require_reason_mixed_attributes() {
+        let input = make_tool_input(
+            "src/main.rs",
+            "#[allow(dead_code, reason = \"justified\")]\nfn a() {}\n#[allow(unused)]\nfn b() {}",
+        );
+        let options = DenyRustAllowOptions {
+            require_reason: true,
+            ..default_options()
+        };
+        let result = deny_rust_allow("Edit", &input, &options).unwrap();
+        let reason = result
+            .hook_specific_output
+            .permission_decision_reason
+            .unwrap();
+        assert!(reason.contains("unused"));
+        assert!(!reason.contains("dead_code"));
+    }
+
+    #[test]
+    fn test_deny_rust_allow_edit_with_no_new_allow_is_none() {
+        let input = ToolInput {
+            old_string: Some("fn untouched() {}".to_string()),
+            new_string: Some("fn untouched() {}\nfn new_fn() {}".to_string()),
+            ..make_tool_input("src/main.rs", "")
+        };
+        assert!(deny_rust_allow("Edit", &input, &default_options()).is_none());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_edit_flags_allow_introduced_by_this_edit() {
+        let input = ToolInput {
+            old_string: Some("fn untouched() {}".to_string()),
+            new_string: Some("fn untouched() {}\n#[allow(dead_code)]\nfn new_fn() {}".to_string()),
+            ..make_tool_input("src/main.rs", "")
+        };
+        assert!(deny_rust_allow("Edit", &input, &default_options()).is_some());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_edit_does_not_flag_allow_carried_through_unchanged() {
+        // Same #[allow(...)] line appears in both old_string and new_string -
+        // it wasn't introduced by this edit, only the line below it was.
+        let input = ToolInput {
+            old_string: Some("#[allow(dead_code)]\nfn old_name() {}".to_string()),
+            new_string: Some("#[allow(dead_code)]\nfn new_name() {}".to_string()),
+            ..make_tool_input("src/main.rs", "")
+        };
+        assert!(deny_rust_allow("Edit", &input, &default_options()).is_none());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_edit_flags_second_allow_with_same_text_as_pre_existing_one() {
+        // old_string already has one #[allow(dead_code)]; new_string carries
+        // that one through unchanged but adds a second, identical-looking
+        // #[allow(dead_code)] elsewhere. Set-membership diffing would hide
+        // the new one behind the pre-existing line's text; a multiset diff
+        // must not.
+        let input = ToolInput {
+            old_string: Some("#[allow(dead_code)]\nfn old_name() {}".to_string()),
+            new_string: Some(
+                "#[allow(dead_code)]\nfn old_name() {}\n#[allow(dead_code)]\nfn new_fn() {}"
+                    .to_string(),
+            ),
+            ..make_tool_input("src/main.rs", "")
+        };
+        assert!(deny_rust_allow("Edit", &input, &default_options()).is_some());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_multi_edit_flags_only_the_hunk_that_adds_allow() {
+        let input = ToolInput {
+            edits: Some(vec![
+                MultiEditOp {
+                    old_string: Some("#[allow(dead_code)]\nfn untouched() {}".to_string()),
+                    new_string: Some("#[allow(dead_code)]\nfn untouched() {}".to_string()),
+                },
+                MultiEditOp {
+                    old_string: Some("fn old_helper() {}".to_string()),
+                    new_string: Some("#[allow(unused)]\nfn new_helper() {}".to_string()),
+                },
+            ]),
+            ..make_tool_input("src/main.rs", "")
+        };
+        let result = deny_rust_allow("MultiEdit", &input, &default_options()).unwrap();
+        let reason = result
+            .hook_specific_output
+            .permission_decision_reason
+            .unwrap();
+        assert!(reason.contains("unused"));
+        assert!(!reason.contains("dead_code"));
+    }
+
+    #[test]
+    fn test_deny_rust_allow_multi_edit_with_no_new_allows_is_none() {
+        let input = ToolInput {
+            edits: Some(vec![MultiEditOp {
+                old_string: Some("#[allow(dead_code)]\nfn old_name() {}".to_string()),
+                new_string: Some("#[allow(dead_code)]\nfn new_name() {}".to_string()),
+            }]),
+            ..make_tool_input("src/main.rs", "")
+        };
+        assert!(deny_rust_allow("MultiEdit", &input, &default_options()).is_none());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_write_checks_whole_content() {
+        let input = make_tool_input("src/main.rs", "");
+        let input = ToolInput {
+            content: Some("#[allow(dead_code)]\nfn foo() {}".to_string()),
+            ..input
+        };
+        assert!(deny_rust_allow("Write", &input, &default_options()).is_some());
+    }
+
+    #[test]
+    fn test_deny_rust_allow_notebook_edit_checks_new_source() {
+        let input = ToolInput {
+            new_source: Some("#[allow(dead_code)]\nfn foo() {}".to_string()),
+            ..make_tool_input("src/main.rs", "")
+        };
+        assert!(deny_rust_allow("NotebookEdit", &input, &default_options()).is_some());
+    }
 }
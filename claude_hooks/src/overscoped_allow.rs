@@ -0,0 +1,273 @@
+//! Overscoped-`#[allow(...)]` detection driven by `cargo clippy`'s JSON
+//! output.
+//!
+//! An `#[allow(lint)]` is "overscoped" when the lint it names is never
+//! actually triggered anywhere in the attribute's covered item: the allow is
+//! dead weight and should be removed, or narrowed to `#[expect(...)]` (which
+//! itself warns once the lint stops firing). This complements `deny_rust_allow`,
+//! which blocks *adding* allows, by catching ones that have gone stale.
+//!
+//! Running `cargo clippy` takes seconds to minutes on a real crate, so
+//! [`check_overscoped_allows`] is never wired up as a blocking `PreToolUse`
+//! hook - it's driven by the `overscoped-allow` audit subcommand (run by
+//! hand or in CI) and by `explain`, both of which are explicit, on-demand
+//! invocations rather than a gate in front of every edit.
+
+use regex::Regex;
+use serde::Deserialize;
+use std::{collections::HashSet, process::Command};
+
+use crate::{HookEventName, HookOutput, HookSpecificOutput, PermissionDecision, RUST_ALLOW_PATTERN};
+use crate::rust_lexer::is_in_comment_or_string;
+
+/// One `#[allow(...)]` (or `#![allow(...)]`) found in the content, with the
+/// line range of the item it's attached to. We don't have a full Rust
+/// parser here, so the "covered item" is approximated as the lines from the
+/// attribute to the end of the following brace-delimited block, or to the
+/// first `;` when one comes before any `{` (see [`covered_item_end_line`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AllowAttribute {
+    lints: Vec<String>,
+    start_line: usize,
+    end_line: usize,
+}
+
+/// A clippy diagnostic warning, as reported by `--message-format=json`.
+#[derive(Debug, Clone)]
+struct ClippyWarning {
+    file: String,
+    lint: String,
+    line: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClippyMessageEnvelope {
+    reason: String,
+    message: Option<ClippyMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClippyMessage {
+    code: Option<ClippyCode>,
+    spans: Vec<ClippySpan>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClippyCode {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClippySpan {
+    file_name: String,
+    line_start: usize,
+    is_primary: bool,
+}
+
+/// Run `cargo clippy --message-format=json` in the current directory and
+/// collect every reported lint warning.
+fn collect_clippy_warnings() -> Vec<ClippyWarning> {
+    let Ok(output) = Command::new("cargo")
+        .args(["clippy", "--message-format=json", "--all-targets"])
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut warnings = Vec::new();
+
+    for line in stdout.lines() {
+        let Ok(envelope) = serde_json::from_str::<ClippyMessageEnvelope>(line) else {
+            continue;
+        };
+        if envelope.reason != "compiler-message" {
+            continue;
+        }
+        let Some(message) = envelope.message else {
+            continue;
+        };
+        let Some(code) = message.code else {
+            continue;
+        };
+        for span in message.spans.iter().filter(|s| s.is_primary) {
+            warnings.push(ClippyWarning {
+                file: span.file_name.clone(),
+                lint: code.code.clone(),
+                line: span.line_start,
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Parse the lint identifiers out of an `#[allow(a, b, clippy::c)]` body.
+fn parse_lint_names(args: &str) -> Vec<String> {
+    args.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(std::string::ToString::to_string)
+        .collect()
+}
+
+/// Find every `#[allow(...)]`/`#![allow(...)]` attribute in `content`, along
+/// with an approximate covered-item line range.
+fn find_allow_attributes(content: &str) -> Vec<AllowAttribute> {
+    let mut attributes = Vec::new();
+
+    for m in RUST_ALLOW_PATTERN.find_iter(content) {
+        if is_in_comment_or_string(content, m.start()) {
+            continue;
+        }
+
+        let Some(open_paren) = content[m.end() - 1..].find('(') else {
+            continue;
+        };
+        let args_start = m.end() - 1 + open_paren + 1;
+        let Some(close_paren_rel) = content[args_start..].find(')') else {
+            continue;
+        };
+        let args = &content[args_start..args_start + close_paren_rel];
+        let lints = parse_lint_names(args);
+        if lints.is_empty() {
+            continue;
+        }
+
+        let start_line = content[..m.start()].matches('\n').count() + 1;
+        let end_line = covered_item_end_line(content, args_start + close_paren_rel, start_line);
+
+        attributes.push(AllowAttribute {
+            lints,
+            start_line,
+            end_line,
+        });
+    }
+
+    attributes
+}
+
+/// Walk forward from the attribute, tracking brace depth, to find the line
+/// the following item closes on. A `;` seen before any `{` ends the item on
+/// that line; if neither closes it, the item runs to the last line of
+/// `content`. Braces and semicolons inside comments or strings are counted
+/// like any others.
+fn covered_item_end_line(content: &str, after: usize, start_line: usize) -> usize {
+    let rest = &content[after..];
+    let mut depth: i32 = 0;
+    let mut seen_open = false;
+    let mut line = start_line;
+
+    for ch in rest.chars() {
+        if ch == '\n' {
+            line += 1;
+        }
+        if ch == '{' {
+            depth += 1;
+            seen_open = true;
+        } else if ch == '}' {
+            depth -= 1;
+            if seen_open && depth <= 0 {
+                return line;
+            }
+        } else if ch == ';' && !seen_open {
+            return line;
+        }
+    }
+
+    line
+}
+
+/// Check whether any lint in `lints` was actually reported by clippy for
+/// `file` within `[start_line, end_line]`.
+fn lint_is_triggered(
+    warnings: &[ClippyWarning],
+    file: &str,
+    lints: &HashSet<String>,
+    start_line: usize,
+    end_line: usize,
+) -> bool {
+    warnings.iter().any(|w| {
+        file.ends_with(w.file.as_str())
+            && w.line >= start_line
+            && w.line <= end_line
+            && lints.iter().any(|lint| lint_names_match(lint, &w.lint))
+    })
+}
+
+/// `#[allow(dead_code)]` should match clippy's `rustc::dead_code`/`dead_code`
+/// codes the same way it matches its own `clippy::` lints.
+fn lint_names_match(allowed: &str, reported: &str) -> bool {
+    let strip_prefix = |s: &str| s.rsplit("::").next().unwrap_or(s);
+    strip_prefix(allowed) == strip_prefix(reported)
+}
+
+/// Find `#[allow(...)]` attributes in `content` (attributed to `file_path`)
+/// whose lint(s) are never triggered by clippy, and return a `PreToolUse`
+/// `Ask` output describing the first one found.
+#[must_use]
+pub fn check_overscoped_allows(file_path: &str, content: &str) -> Option<HookOutput> {
+    let attributes = find_allow_attributes(content);
+    if attributes.is_empty() {
+        return None;
+    }
+
+    let warnings = collect_clippy_warnings();
+
+    for attr in &attributes {
+        let lints: HashSet<String> = attr.lints.iter().cloned().collect();
+        if !lint_is_triggered(&warnings, file_path, &lints, attr.start_line, attr.end_line) {
+            let lint_list = attr.lints.join(", ");
+            return Some(HookOutput {
+                hook_specific_output: HookSpecificOutput {
+                    hook_event_name: HookEventName::PreToolUse,
+                    decision: None,
+                    permission_decision: Some(PermissionDecision::Ask),
+                    permission_decision_reason: Some(format!(
+                        "#[allow({lint_list})] on line {} of {file_path} appears overscoped: \
+                         clippy never reports {lint_list} inside the annotated item. Remove \
+                         the allow or narrow it to #[expect({lint_list})].",
+                        attr.start_line
+                    )),
+                },
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lint_names_splits_and_trims() {
+        assert_eq!(
+            parse_lint_names(" dead_code , clippy::pedantic "),
+            vec!["dead_code".to_string(), "clippy::pedantic".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_find_allow_attributes_single_item() {
+        let content = "#[allow(dead_code)]\nfn foo() {\n    let x = 1;\n}\n";
+        let attrs = find_allow_attributes(content);
+        assert_eq!(attrs.len(), 1);
+        assert_eq!(attrs[0].lints, vec!["dead_code".to_string()]);
+        assert_eq!(attrs[0].start_line, 1);
+        assert_eq!(attrs[0].end_line, 4);
+    }
+
+    #[test]
+    fn test_lint_names_match_strips_tool_prefix() {
+        assert!(lint_names_match("dead_code", "rustc::dead_code"));
+        assert!(lint_names_match("clippy::needless_clone", "needless_clone"));
+        assert!(!lint_names_match("dead_code", "unused"));
+    }
+
+    #[test]
+    fn test_check_overscoped_allows_with_no_attributes_is_none() {
+        assert!(check_overscoped_allows("src/main.rs", "fn foo() {}").is_none());
+    }
+}
@@ -0,0 +1,234 @@
+//! Configurable command policy engine for `permission-request`.
+//!
+//! Historically `block_rm` and `confirm_destructive_find` were the only two
+//! rules a user could get, both baked into the binary as static regexes.
+//! This module lets users layer their own rules on top (or replace the
+//! defaults outright) via a TOML config file, borrowing Deno's allow/deny
+//! permission model: an ordered allow-list is checked first and short-
+//! circuits the match, then an ordered deny/ask list is evaluated in turn.
+
+use regex::Regex;
+use serde::Deserialize;
+use std::{fs, io, path::PathBuf};
+
+use crate::{
+    Decision, DecisionBehavior, HookEventName, HookOutput, HookSpecificOutput, PermissionDecision,
+};
+
+/// Decision a matched rule should produce.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleDecision {
+    Deny,
+    Ask,
+    Allow,
+}
+
+/// A single named rule, matched against the Bash `command` string.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolicyRule {
+    pub name: String,
+    pub pattern: String,
+    pub decision: RuleDecision,
+    pub message: String,
+}
+
+/// A loaded (or default) set of rules for `permission-request`.
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct Policy {
+    /// Rules checked first; any match short-circuits with `Allow` (i.e. no
+    /// hook output at all, letting Claude Code's own prompt take over).
+    #[serde(default)]
+    pub allow: Vec<PolicyRule>,
+    /// Rules checked after the allow-list, in order; first match wins.
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+impl Policy {
+    /// The built-in ruleset: today's `block_rm` and `confirm_destructive_find`,
+    /// expressed in the same rule format users can override or extend.
+    #[must_use]
+    pub fn defaults() -> Self {
+        let mut rules = vec![PolicyRule {
+            name: "block-rm".to_string(),
+            pattern: crate::RM_PATTERN.as_str().to_string(),
+            decision: RuleDecision::Deny,
+            message: "rm is forbidden. Use trash command to delete files. Example: trash <path...>".to_string(),
+        }];
+
+        for (pattern, description) in crate::DESTRUCTIVE_PATTERNS {
+            rules.push(PolicyRule {
+                name: format!("destructive-find: {description}"),
+                pattern: format!("(?i){pattern}"),
+                decision: RuleDecision::Ask,
+                message: format!(
+                    "Destructive find command detected: {description}. \
+                     This operation may delete or modify files. Please confirm."
+                ),
+            });
+        }
+
+        Self {
+            allow: Vec::new(),
+            rules,
+        }
+    }
+
+    /// Default config path: `~/.config/claude_hooks/policy.toml`.
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs_config_home().map(|home| home.join("claude_hooks").join("policy.toml"))
+    }
+
+    /// Load a policy file from disk.
+    pub fn load(path: &std::path::Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Merge user-supplied rules in front of the built-in defaults: a
+    /// user rule of the same name overrides the default, and new rules are
+    /// appended ahead of the defaults so they're evaluated first.
+    #[must_use]
+    pub fn merged_with_defaults(mut self) -> Self {
+        let defaults = Self::defaults();
+
+        let mut rules = self.rules;
+        for default_rule in defaults.rules {
+            if !rules.iter().any(|r| r.name == default_rule.name) {
+                rules.push(default_rule);
+            }
+        }
+        self.rules = rules;
+        self
+    }
+
+    /// Evaluate a command against this policy, returning the hook output
+    /// for the first matching rule (allow-list first, then deny/ask rules
+    /// in order).
+    #[must_use]
+    pub fn evaluate(&self, cmd: &str) -> Option<HookOutput> {
+        for rule in &self.allow {
+            if let Ok(re) = Regex::new(&rule.pattern) {
+                if re.is_match(cmd) {
+                    return None;
+                }
+            }
+        }
+
+        for rule in &self.rules {
+            let Ok(re) = Regex::new(&rule.pattern) else {
+                continue;
+            };
+            if !re.is_match(cmd) {
+                continue;
+            }
+
+            return Some(match rule.decision {
+                RuleDecision::Deny => HookOutput {
+                    hook_specific_output: HookSpecificOutput {
+                        hook_event_name: HookEventName::PermissionRequest,
+                        decision: Some(Decision {
+                            behavior: DecisionBehavior::Deny,
+                            message: rule.message.clone(),
+                        }),
+                        permission_decision: None,
+                        permission_decision_reason: None,
+                    },
+                },
+                RuleDecision::Ask => HookOutput {
+                    hook_specific_output: HookSpecificOutput {
+                        hook_event_name: HookEventName::PermissionRequest,
+                        decision: None,
+                        permission_decision: Some(PermissionDecision::Ask),
+                        permission_decision_reason: Some(rule.message.clone()),
+                    },
+                },
+                RuleDecision::Allow => return None,
+            });
+        }
+
+        None
+    }
+}
+
+fn dirs_config_home() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_block_rm() {
+        let policy = Policy::defaults();
+        let output = policy.evaluate("rm -rf /tmp/test").unwrap();
+        assert!(matches!(
+            output.hook_specific_output.decision.unwrap().behavior,
+            DecisionBehavior::Deny
+        ));
+    }
+
+    #[test]
+    fn test_defaults_allow_safe_command() {
+        let policy = Policy::defaults();
+        assert!(policy.evaluate("ls -la").is_none());
+    }
+
+    #[test]
+    fn test_allow_list_short_circuits_deny() {
+        let mut policy = Policy::defaults();
+        assert!(policy.evaluate("rm -i file.txt").is_some());
+        policy.allow.push(PolicyRule {
+            name: "allow-interactive-rm".to_string(),
+            pattern: r"^rm\s+-i\b".to_string(),
+            decision: RuleDecision::Allow,
+            message: String::new(),
+        });
+        assert!(policy.evaluate("rm -i file.txt").is_none());
+    }
+
+    #[test]
+    fn test_custom_deny_rule_runs_before_defaults() {
+        let mut policy = Policy {
+            allow: Vec::new(),
+            rules: vec![PolicyRule {
+                name: "block-rm".to_string(),
+                pattern: r"^rm\b".to_string(),
+                decision: RuleDecision::Deny,
+                message: "custom message".to_string(),
+            }],
+        };
+        policy = policy.merged_with_defaults();
+        let output = policy.evaluate("rm file.txt").unwrap();
+        assert_eq!(
+            output
+                .hook_specific_output
+                .decision
+                .unwrap()
+                .message,
+            "custom message"
+        );
+    }
+
+    #[test]
+    fn test_merged_with_defaults_keeps_unrelated_user_rules() {
+        let policy = Policy {
+            allow: Vec::new(),
+            rules: vec![PolicyRule {
+                name: "block-curl-to-internal".to_string(),
+                pattern: r"curl .*10\.0\.0\.".to_string(),
+                decision: RuleDecision::Deny,
+                message: "internal network access is blocked".to_string(),
+            }],
+        }
+        .merged_with_defaults();
+
+        assert!(policy.evaluate("curl http://10.0.0.5").is_some());
+        assert!(policy.evaluate("rm file.txt").is_some());
+    }
+}
@@ -0,0 +1,458 @@
+//! Minimal Rust tokenizer used to tell whether a byte offset in a source
+//! snippet falls inside a comment, char literal, or string literal.
+//!
+//! The previous implementation counted occurrences of `//`, `/*`, `*/`, and
+//! quote characters, which went wrong on nested block comments, char
+//! literals like `'"'`, and raw strings with more than one `#`. This walks
+//! the content once as a real state machine instead.
+
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Code,
+    LineComment,
+    BlockComment { depth: u32 },
+    Char,
+    Str,
+    RawStr { hashes: u32 },
+}
+
+/// Check if a position in the content is inside a line comment, a block
+/// comment, a char literal, or a string literal (including raw strings).
+pub(crate) fn is_in_comment_or_string(content: &str, match_start: usize) -> bool {
+    let bytes = content.as_bytes();
+    let mut state = State::Code;
+    let mut i = 0;
+
+    while i < match_start && i < bytes.len() {
+        match state {
+            State::Code => {
+                if bytes[i..].starts_with(b"//") {
+                    state = State::LineComment;
+                    i += 2;
+                    continue;
+                }
+                if bytes[i..].starts_with(b"/*") {
+                    state = State::BlockComment { depth: 1 };
+                    i += 2;
+                    continue;
+                }
+                if bytes[i] == b'"' {
+                    state = State::Str;
+                    i += 1;
+                    continue;
+                }
+                if bytes[i] == b'\'' {
+                    // Only treat this as a char literal if it plausibly
+                    // closes within a few bytes (`'x'` or `'\''`), so a
+                    // lifetime or generic tick like `'a` doesn't eat the
+                    // rest of the file.
+                    if looks_like_char_literal(bytes, i) {
+                        state = State::Char;
+                    }
+                    i += 1;
+                    continue;
+                }
+                if let Some(hashes) = raw_string_prefix_len(bytes, i) {
+                    state = State::RawStr { hashes };
+                    i += 2 + hashes as usize;
+                    continue;
+                }
+                i += 1;
+            }
+            State::LineComment => {
+                if bytes[i] == b'\n' {
+                    state = State::Code;
+                }
+                i += 1;
+            }
+            State::BlockComment { depth } => {
+                if bytes[i..].starts_with(b"/*") {
+                    state = State::BlockComment { depth: depth + 1 };
+                    i += 2;
+                } else if bytes[i..].starts_with(b"*/") {
+                    state = if depth > 1 {
+                        State::BlockComment { depth: depth - 1 }
+                    } else {
+                        State::Code
+                    };
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            State::Char => {
+                if bytes[i] == b'\\' {
+                    i += 2;
+                } else if bytes[i] == b'\'' {
+                    state = State::Code;
+                    i += 1;
+                } else {
+                    i += 1;
+                }
+            }
+            State::Str => {
+                if bytes[i] == b'\\' {
+                    i += 2;
+                } else if bytes[i] == b'"' {
+                    state = State::Code;
+                    i += 1;
+                } else {
+                    i += 1;
+                }
+            }
+            State::RawStr { hashes } => {
+                if bytes[i] == b'"' && has_closing_hashes(bytes, i + 1, hashes) {
+                    state = State::Code;
+                    i += 1 + hashes as usize;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    !matches!(state, State::Code)
+}
+
+/// `'x'`, `'\''`, `'\n'`, `'\\'` all plausibly close within 4 bytes; a bare
+/// `'a` generic/lifetime tick does not.
+fn looks_like_char_literal(bytes: &[u8], quote_pos: usize) -> bool {
+    let rest = &bytes[quote_pos + 1..];
+    if rest.first() == Some(&b'\\') {
+        // \x, \n, \', \\, ... followed by a closing quote within a few bytes.
+        return rest.iter().take(5).skip(1).any(|&b| b == b'\'');
+    }
+    rest.len() >= 2 && rest[1] == b'\''
+}
+
+fn raw_string_prefix_len(bytes: &[u8], pos: usize) -> Option<u32> {
+    if bytes.get(pos) != Some(&b'r') {
+        return None;
+    }
+    let mut j = pos + 1;
+    let mut hashes = 0u32;
+    while bytes.get(j) == Some(&b'#') {
+        hashes += 1;
+        j += 1;
+    }
+    if bytes.get(j) == Some(&b'"') {
+        Some(hashes)
+    } else {
+        None
+    }
+}
+
+fn has_closing_hashes(bytes: &[u8], pos: usize, hashes: u32) -> bool {
+    (0..hashes).all(|offset| bytes.get(pos + offset as usize) == Some(&b'#'))
+}
+
+/// Find if there are real matches of a pattern (not in comments or strings).
+pub(crate) fn find_real_matches(content: &str, pattern: &Regex) -> bool {
+    for m in pattern.find_iter(content) {
+        if !is_in_comment_or_string(content, m.start()) {
+            return true;
+        }
+    }
+    false
+}
+
+/// One occurrence of a named attribute, found either as a bare
+/// `#[name(...)]`/`#![name(...)]` or nested inside
+/// `#[cfg_attr(condition, name(...))]`. `start` is the byte offset of the
+/// leading `#`, used for line lookups and the escape-hatch check; `args` is
+/// the raw, un-split text between the attribute's parens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AttributeMatch {
+    pub(crate) start: usize,
+    pub(crate) args: String,
+}
+
+/// Scan `content` for every attribute named `name`. Tolerates arbitrary
+/// whitespace and line breaks between `#`, `!`, `[`, the identifier, and the
+/// opening `(` (so `#[ allow ( dead_code ) ]` and attributes split across
+/// several lines are both found), and unwraps
+/// `#[cfg_attr(condition, name(...))]` to inspect the nested attribute.
+/// Occurrences inside comments or string/char literals are skipped, the same
+/// as `is_in_comment_or_string`.
+pub(crate) fn find_attribute_matches(content: &str, name: &str) -> Vec<AttributeMatch> {
+    let bytes = content.as_bytes();
+    let mut matches = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'#' || is_in_comment_or_string(content, i) {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let mut j = skip_ws(bytes, i + 1);
+        if bytes.get(j) == Some(&b'!') {
+            j += 1;
+        }
+        j = skip_ws(bytes, j);
+        if bytes.get(j) != Some(&b'[') {
+            i += 1;
+            continue;
+        }
+        j = skip_ws(bytes, j + 1);
+
+        let (ident, after_ident) = read_ident(bytes, j);
+        if ident.is_empty() {
+            i += 1;
+            continue;
+        }
+        let after_ws = skip_ws(bytes, after_ident);
+        let Some((args, after_paren)) = read_balanced_parens(bytes, after_ws) else {
+            i = after_ident;
+            continue;
+        };
+
+        if ident == name {
+            matches.push(AttributeMatch {
+                start,
+                args: args.to_string(),
+            });
+        } else if ident == "cfg_attr" {
+            for (nested_name, nested_args) in split_cfg_attr_nested(args) {
+                if nested_name == name {
+                    matches.push(AttributeMatch {
+                        start,
+                        args: nested_args,
+                    });
+                }
+            }
+        }
+
+        i = after_paren;
+    }
+
+    matches
+}
+
+fn skip_ws(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+fn read_ident(bytes: &[u8], start: usize) -> (&str, usize) {
+    let mut i = start;
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+        i += 1;
+    }
+    (std::str::from_utf8(&bytes[start..i]).unwrap_or(""), i)
+}
+
+/// Read a balanced `(...)` group starting at `open`, returning its interior
+/// (not including the parens) and the index just past the closing `)`.
+fn read_balanced_parens(bytes: &[u8], open: usize) -> Option<(&str, usize)> {
+    if bytes.get(open) != Some(&b'(') {
+        return None;
+    }
+    let mut depth = 0i32;
+    let mut i = open;
+    let inner_start = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return std::str::from_utf8(&bytes[inner_start..i])
+                        .ok()
+                        .map(|s| (s, i + 1));
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split the args of `cfg_attr(condition, attr1(...), attr2, ...)` into its
+/// nested `(name, args)` attributes, skipping the leading condition.
+fn split_cfg_attr_nested(args: &str) -> Vec<(String, String)> {
+    split_top_level_commas(args)
+        .into_iter()
+        .skip(1)
+        .filter_map(|segment| {
+            let bytes = segment.as_bytes();
+            let (ident, after_ident) = read_ident(bytes, 0);
+            if ident.is_empty() {
+                return None;
+            }
+            let after_ws = skip_ws(bytes, after_ident);
+            let (nested_args, _) = read_balanced_parens(bytes, after_ws)?;
+            Some((ident.to_string(), nested_args.to_string()))
+        })
+        .collect()
+}
+
+/// Split `args` on top-level commas, i.e. commas not nested inside a further
+/// `(...)` group or a string literal, so an attribute's own argument list -
+/// including a `reason = "..., still one item"` item - isn't torn apart.
+pub(crate) fn split_top_level_commas(args: &str) -> Vec<&str> {
+    let bytes = args.as_bytes();
+    let mut segments = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => {
+                i += 2;
+                continue;
+            }
+            b'"' => in_string = !in_string,
+            b'(' if !in_string => depth += 1,
+            b')' if !in_string => depth -= 1,
+            b',' if !in_string && depth == 0 => {
+                segments.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    segments.push(args[start..].trim());
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::LazyLock;
+
+    static ALLOW_PATTERN: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"#!?\[allow\s*\(").unwrap());
+
+    #[test]
+    fn test_line_comment() {
+        let content = "// #[allow(dead_code)]";
+        assert!(is_in_comment_or_string(content, 3));
+    }
+
+    #[test]
+    fn test_not_in_comment() {
+        let content = "#[allow(dead_code)]";
+        assert!(!is_in_comment_or_string(content, 0));
+    }
+
+    #[test]
+    fn test_nested_block_comment() {
+        let content = "/* outer /* inner */ #[allow(dead_code)] */";
+        assert!(is_in_comment_or_string(content, 22));
+    }
+
+    #[test]
+    fn test_string_literal() {
+        let content = "let s = \"#[allow(dead_code)]\";";
+        assert!(is_in_comment_or_string(content, 9));
+    }
+
+    #[test]
+    fn test_url_in_string_is_not_mistaken_for_comment() {
+        // Previously `.contains("//")` treated this whole line as a comment.
+        let content = "let url = \"http://example.com\"; #[allow(dead_code)]";
+        assert!(!is_in_comment_or_string(content, content.find("#[allow").unwrap()));
+    }
+
+    #[test]
+    fn test_char_literal_quote_does_not_desync_strings() {
+        let content = "let c = '\"'; #[allow(dead_code)]";
+        assert!(!is_in_comment_or_string(content, content.find("#[allow").unwrap()));
+    }
+
+    #[test]
+    fn test_raw_string_with_double_hash() {
+        let content = "let s = r##\"#[allow(dead_code)]\"##; #[allow(dead_code)]";
+        let second = content.rfind("#[allow").unwrap();
+        assert!(!is_in_comment_or_string(content, second));
+    }
+
+    #[test]
+    fn test_raw_string_single_hash_not_closed_by_plain_quote() {
+        let content = "r#\"still \" open\"# trailing";
+        // position well inside the raw string body
+        let pos = content.find("open").unwrap();
+        assert!(is_in_comment_or_string(content, pos));
+    }
+
+    #[test]
+    fn test_find_real_matches_ignores_comments() {
+        let content = "// #[allow(dead_code)]\nfn foo() {}";
+        assert!(!find_real_matches(content, &ALLOW_PATTERN));
+    }
+
+    #[test]
+    fn test_find_real_matches_detects_real_allow() {
+        let content = "#[allow(dead_code)]\nfn foo() {}";
+        assert!(find_real_matches(content, &ALLOW_PATTERN));
+    }
+
+    #[test]
+    fn test_find_attribute_matches_basic() {
+        let content = "#[allow(dead_code)]\nfn foo() {}";
+        let matches = find_attribute_matches(content, "allow");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].args, "dead_code");
+        assert_eq!(matches[0].start, 0);
+    }
+
+    #[test]
+    fn test_find_attribute_matches_inner_attribute() {
+        let content = "#![allow(unused)]\nfn foo() {}";
+        let matches = find_attribute_matches(content, "allow");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].args, "unused");
+    }
+
+    #[test]
+    fn test_find_attribute_matches_ignores_comments_and_strings() {
+        let content = "// #[allow(dead_code)]\nlet s = \"#[allow(dead_code)]\";";
+        assert!(find_attribute_matches(content, "allow").is_empty());
+    }
+
+    #[test]
+    fn test_find_attribute_matches_tolerates_whitespace() {
+        let content = "#[ allow ( dead_code ) ]\nfn foo() {}";
+        let matches = find_attribute_matches(content, "allow");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].args, " dead_code ");
+    }
+
+    #[test]
+    fn test_find_attribute_matches_spans_multiple_lines() {
+        let content = "#[allow(\n    dead_code,\n    unused\n)]\nfn foo() {}";
+        let matches = find_attribute_matches(content, "allow");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].args, "\n    dead_code,\n    unused\n");
+    }
+
+    #[test]
+    fn test_find_attribute_matches_unwraps_cfg_attr() {
+        let content = "#[cfg_attr(test, allow(dead_code))]\nfn foo() {}";
+        let matches = find_attribute_matches(content, "allow");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].args, "dead_code");
+    }
+
+    #[test]
+    fn test_find_attribute_matches_cfg_attr_unrelated_name_not_matched() {
+        let content = "#[cfg_attr(test, derive(Debug))]\nfn foo() {}";
+        assert!(find_attribute_matches(content, "allow").is_empty());
+    }
+
+    #[test]
+    fn test_split_top_level_commas_ignores_commas_inside_string() {
+        let segments = split_top_level_commas(r#"dead_code, reason = "a, b, c""#);
+        assert_eq!(segments, vec!["dead_code", r#"reason = "a, b, c""#]);
+    }
+}
@@ -0,0 +1,248 @@
+//! Path-scoped write guard for Edit/Write tools.
+//!
+//! Modeled on Deno's `--allow-write`/`--deny-write` path lists: resolves the
+//! tool's target file path against the working directory into an absolute,
+//! symlink-resolved path, then checks it against configurable allow-root and
+//! deny-root lists before letting an Edit or Write through.
+
+use std::path::{Path, PathBuf};
+
+use crate::{HookEventName, HookOutput, HookSpecificOutput, PermissionDecision, ToolInput};
+
+/// Options for `guard_write`.
+#[derive(Debug, Clone, Default)]
+pub struct WriteGuardOptions {
+    /// Root directories writes are permitted under. Empty means "no
+    /// restriction" (anything not explicitly denied is allowed).
+    pub allow: Vec<PathBuf>,
+    /// Root directories writes are always denied under, regardless of `allow`.
+    pub deny: Vec<PathBuf>,
+}
+
+impl WriteGuardOptions {
+    /// Append the built-in deny roots relative to `cwd`: version control
+    /// metadata, build output, and system config should never be edited
+    /// directly by an agent.
+    #[must_use]
+    pub fn with_builtin_denies(mut self, cwd: &Path) -> Self {
+        for name in [".git", "target"] {
+            self.deny.push(cwd.join(name));
+        }
+        if !cfg!(windows) {
+            self.deny.push(PathBuf::from("/etc"));
+        }
+        self
+    }
+}
+
+/// Resolve `file_path` against `cwd` into an absolute, symlink-resolved path.
+///
+/// If the leaf itself already exists, canonicalize the *whole* path - not
+/// just its parent - so a symlink planted as the leaf (e.g. `src/evil ->
+/// /etc/passwd`, sitting inside an otherwise-allowed root) resolves to its
+/// real target before the allow/deny check, instead of the check running
+/// against the symlink's in-root location while the write actually lands
+/// outside every configured root. Only fall back to canonicalizing the
+/// parent and joining the literal file name for the not-yet-created-file
+/// case, where the leaf can't be canonicalized because it doesn't exist yet.
+///
+/// Returns `None` if neither the full path nor its parent can be resolved
+/// (e.g. the parent directory doesn't exist) - callers must treat that as
+/// "deny", never as "allow", since a relative path we can't fully resolve
+/// could still escape an allowed root via `..` components we failed to
+/// normalize.
+fn resolve_write_path(file_path: &str, cwd: &Path) -> Option<PathBuf> {
+    if file_path.is_empty() {
+        return None;
+    }
+
+    let path = Path::new(file_path);
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        cwd.join(path)
+    };
+
+    if let Ok(canonical) = std::fs::canonicalize(&absolute) {
+        return Some(canonical);
+    }
+
+    let parent = absolute.parent()?;
+    let file_name = absolute.file_name()?;
+    let canonical_parent = std::fs::canonicalize(parent).ok()?;
+    Some(canonical_parent.join(file_name))
+}
+
+fn is_under(path: &Path, root: &Path) -> bool {
+    let canonical_root = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    path.starts_with(canonical_root)
+}
+
+fn deny(message: String) -> HookOutput {
+    HookOutput {
+        hook_specific_output: HookSpecificOutput {
+            hook_event_name: HookEventName::PreToolUse,
+            decision: None,
+            permission_decision: Some(PermissionDecision::Deny),
+            permission_decision_reason: Some(message),
+        },
+    }
+}
+
+/// Deny an Edit/Write whose resolved path falls outside the allow-list (if
+/// one is configured) or inside a deny-list root.
+///
+/// `cwd` being `None` (the working directory couldn't be determined) is
+/// treated the same as an unresolvable path: deny rather than silently allow.
+#[must_use]
+pub fn guard_write(
+    tool_name: &str,
+    tool_input: &ToolInput,
+    cwd: Option<&Path>,
+    options: &WriteGuardOptions,
+) -> Option<HookOutput> {
+    if tool_name != "Edit" && tool_name != "Write" {
+        return None;
+    }
+
+    let file_path = tool_input.file_path.as_deref().unwrap_or_default();
+    if file_path.is_empty() {
+        return None;
+    }
+
+    let Some(cwd) = cwd else {
+        return Some(deny(
+            "could not resolve the current working directory; denying write as a precaution"
+                .to_string(),
+        ));
+    };
+
+    let Some(resolved) = resolve_write_path(file_path, cwd) else {
+        return Some(deny(format!(
+            "could not resolve `{file_path}` to a real path (missing parent directory or \
+             broken symlink); denying write as a precaution"
+        )));
+    };
+
+    for root in &options.deny {
+        if is_under(&resolved, root) {
+            return Some(deny(format!(
+                "writing to `{}` is denied: it falls under the protected path `{}`",
+                resolved.display(),
+                root.display()
+            )));
+        }
+    }
+
+    if !options.allow.is_empty() && !options.allow.iter().any(|root| is_under(&resolved, root)) {
+        return Some(deny(format!(
+            "writing to `{}` is denied: it is outside the configured allow-write roots",
+            resolved.display()
+        )));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_input(file_path: &str) -> ToolInput {
+        ToolInput {
+            command: None,
+            old_string: None,
+            new_string: None,
+            content: None,
+            edits: None,
+            new_source: None,
+            file_path: Some(file_path.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_ignores_non_write_tools() {
+        let cwd = std::env::current_dir().unwrap();
+        let input = tool_input("src/main.rs");
+        assert!(guard_write("Bash", &input, Some(&cwd), &WriteGuardOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_denies_when_cwd_missing() {
+        let input = tool_input("src/main.rs");
+        assert!(guard_write("Edit", &input, None, &WriteGuardOptions::default()).is_some());
+    }
+
+    #[test]
+    fn test_allows_write_within_cwd_by_default() {
+        let cwd = std::env::current_dir().unwrap();
+        let input = tool_input("src/main.rs");
+        assert!(guard_write("Edit", &input, Some(&cwd), &WriteGuardOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_denies_protected_git_directory() {
+        let cwd = std::env::current_dir().unwrap();
+        let options = WriteGuardOptions::default().with_builtin_denies(&cwd);
+        let input = tool_input(".git/config");
+        assert!(guard_write("Edit", &input, Some(&cwd), &options).is_some());
+    }
+
+    #[test]
+    fn test_denies_path_outside_allow_roots() {
+        let cwd = std::env::current_dir().unwrap();
+        let options = WriteGuardOptions {
+            allow: vec![cwd.join("src")],
+            deny: Vec::new(),
+        };
+        let input = tool_input("/tmp/outside.rs");
+        assert!(guard_write("Edit", &input, Some(&cwd), &options).is_some());
+    }
+
+    #[test]
+    fn test_allows_path_inside_allow_roots() {
+        let cwd = std::env::current_dir().unwrap();
+        let options = WriteGuardOptions {
+            allow: vec![cwd.join("src")],
+            deny: Vec::new(),
+        };
+        let input = tool_input("src/main.rs");
+        assert!(guard_write("Edit", &input, Some(&cwd), &options).is_none());
+    }
+
+    #[test]
+    fn test_denies_unresolvable_parent_directory() {
+        let cwd = std::env::current_dir().unwrap();
+        let input = tool_input("this/directory/does/not/exist/file.rs");
+        assert!(guard_write("Edit", &input, Some(&cwd), &WriteGuardOptions::default()).is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_denies_leaf_symlink_escaping_allow_root() {
+        // src/evil is a symlink sitting inside the allowed `src` root, but
+        // it points outside of it - the real write target must be resolved
+        // (and denied) even though the symlink's own path looks allowed.
+        let dir = std::env::temp_dir().join(format!(
+            "claude-hooks-write-guard-symlink-test-{}",
+            std::process::id()
+        ));
+        let allowed_root = dir.join("src");
+        let outside_root = dir.join("outside");
+        std::fs::create_dir_all(&allowed_root).unwrap();
+        std::fs::create_dir_all(&outside_root).unwrap();
+        let target = outside_root.join("passwd");
+        std::fs::write(&target, "secret").unwrap();
+        let symlink = allowed_root.join("evil");
+        std::os::unix::fs::symlink(&target, &symlink).unwrap();
+
+        let options = WriteGuardOptions {
+            allow: vec![allowed_root.clone()],
+            deny: Vec::new(),
+        };
+        let input = tool_input(symlink.to_str().unwrap());
+        assert!(guard_write("Edit", &input, Some(&dir), &options).is_some());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}